@@ -2,14 +2,41 @@
 //! It is used to make requests to the API. It is created with a token, which is used to authenticate the requests.
 //! You can get a token from <https://the-one-api.dev/>.
 
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+
 use crate::{
-    request::{Request, Requester},
-    Book, Chapter, Character, Error, Item, ItemType, Movie, Quote, Response,
+    attribute::Attribute,
+    filter::{Filter, FilterValue, Operator},
+    item::hydrated::{Cache, Hydrate, References},
+    request::{
+        decode::DecodeMode,
+        page::Page,
+        plan::Plan,
+        rate_limit::{RateLimit, RateLimiter},
+        retry::RetryPolicy,
+        GetUrl, Request, RequestBuilder, Requester, TypedRequest,
+    },
+    transport::{ReqwestTransport, Transport},
+    ApiObject, Book, BookId, Chapter, ChapterId, Character, CharacterId, Error, Item, ItemType,
+    Movie, MovieId, Quote, QuoteId, Response, TypedItem,
 };
 
+/// The url of the API that is used when no other base url is set.
+pub const DEFAULT_BASE_URL: &str = "https://the-one-api.dev/v2/";
+
+/// The number of ids that are fetched by a single request when hydrating items.
+const HYDRATION_BATCH_SIZE: usize = 100;
+
 /// The client for the one api to rule them all.
 /// It is used to make requests to the API.
 ///
+/// A client keeps a single pool of connections that is shared by all of its
+/// requests. Cloning it is cheap and the clones share that same pool, so prefer
+/// cloning an existing client over creating a new one.
+///
 /// # Examples
 /// ```rust, no_run
 /// use lotr_api::Client;
@@ -21,157 +48,236 @@ use crate::{
 ///     // ...
 /// }
 /// ```
+#[derive(Debug, Clone)]
 pub struct Client {
     requester: Requester,
+    decode_mode: DecodeMode,
 }
 
 impl Client {
     /// Creates a new client with the given token.
     /// The token is used to authenticate the requests.
     /// You can get a token from <https://the-one-api.dev/>.
+    ///
+    /// # Panics
+    /// If the client can't be created, for example if the token contains
+    /// characters that are not allowed in a header. Use [`ClientBuilder`]
+    /// to handle this case.
     pub fn new(token: String) -> Self {
-        Self {
-            requester: Requester::new(token),
-        }
+        ClientBuilder::new(token)
+            .build()
+            .expect("Failed to build client")
+    }
+
+    /// Returns a [`ClientBuilder`] to configure a client that uses the given token.
+    pub fn builder(token: String) -> ClientBuilder {
+        ClientBuilder::new(token)
+    }
+
+    /// Returns the number of requests that can be made right now without
+    /// hitting the rate limit, or `None` if the client has no rate limit.
+    /// See [`ClientBuilder::rate_limit`].
+    pub fn remaining_requests(&self) -> Option<u32> {
+        self.requester.remaining_requests()
     }
 
     async fn request_with_url<T>(&self, url: &str) -> Result<Response<T>, Error>
     where
         T: serde::de::DeserializeOwned,
     {
-        let response = self.requester.get(url).await?;
-        let response: Response<T> = serde_json::from_str(&response).map_err(Error::from)?;
-        Ok(response)
+        self.requester.get_json(url).await
     }
 
-    async fn request<T>(&self, request: Request) -> Result<Response<T>, Error>
-    where
-        T: serde::de::DeserializeOwned,
-    {
-        let response = self.requester.get_from_request(request).await?;
-        let response: Response<T> = serde_json::from_str(&response).map_err(Error::from)?;
-        Ok(response)
+    /// Returns all the items of the given type, walking through all the pages.
+    async fn get_all<T: TypedItem>(&self) -> Result<Vec<T>, Error> {
+        self.stream_typed::<T>(Request::new(T::item_type()))
+            .try_collect()
+            .await
     }
 
     /// Returns all books.
     pub async fn get_books(&self) -> Result<Vec<Book>, Error> {
-        Ok(self.request_with_url::<Book>("book").await?.get_contents())
+        self.get_all().await
     }
 
     /// Returns all movies.
     pub async fn get_movies(&self) -> Result<Vec<Movie>, Error> {
-        Ok(self
-            .request_with_url::<Movie>("movie")
-            .await?
-            .get_contents())
+        self.get_all().await
     }
 
-    /// Returns all the quotes. Due to the API default limit of 1000,
-    /// this function has to set a hardcoded limit.
-    /// Currently there are  2383 quotes on the api, so we call it with a limit of 2400
-    /// to have a little bit of buffer in case the number of quotes increases
+    /// Returns all the quotes.
     pub async fn get_quotes(&self) -> Result<Vec<Quote>, Error> {
-        Ok(self
-            .request_with_url::<Quote>("quote?limit=2400")
-            .await?
-            .get_contents())
+        self.get_all().await
     }
 
-    // Returns all the characters.
+    /// Returns all the characters.
     pub async fn get_characters(&self) -> Result<Vec<Character>, Error> {
-        Ok(self
-            .request_with_url::<Character>("character")
-            .await?
-            .get_contents())
+        self.get_all().await
     }
 
     /// Returns all chapters.
     pub async fn get_chapters(&self) -> Result<Vec<Chapter>, Error> {
-        Ok(self
-            .request_with_url::<Chapter>("chapter")
-            .await?
-            .get_contents())
+        self.get_all().await
     }
 
-    /// Returns the book with the given id.
+    /// Returns the object of the given type with the given id.
     ///
     /// # Errors
-    /// If there is no book with the given id, an error is returned.
-    pub async fn get_book_by_id(&self, id: &str) -> Result<Book, Error> {
-        let url = format!("book/{}", id);
-        let mut books = self.request_with_url::<Book>(&url).await?.get_contents();
-        books
-            .pop()
-            .ok_or(Error::Other("No book with id {} found".to_string()))
+    /// If there is no object with the given id, an [`Error::NotFound`] is returned.
+    ///
+    /// # Examples
+    /// ```rust, no_run
+    /// use lotr_api::{Client, Movie, MovieId};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("your_token".to_string());
+    ///     let id: MovieId = "5cd95395de30eff6ebccde5d".parse().unwrap();
+    ///     let movie: Movie = client.get_by_id(&id).await.unwrap();
+    ///     // ...
+    /// }
+    /// ```
+    pub async fn get_by_id<T: ApiObject>(&self, id: &T::Id) -> Result<T, Error> {
+        let url = format!("{}/{}", T::item_type(), id);
+        let mut items = self
+            .request_with_url::<T>(&url)
+            .await
+            .map_err(|error| error.or_not_found(T::item_type(), id))?
+            .get_contents();
+        items.pop().ok_or_else(|| Error::NotFound {
+            item_type: T::item_type(),
+            id: id.to_string(),
+        })
+    }
+
+    /// Returns the book with the given id. See [`Client::get_by_id`].
+    pub async fn get_book_by_id(&self, id: &BookId) -> Result<Book, Error> {
+        self.get_by_id(id).await
+    }
+
+    /// Returns the movie with the given id. See [`Client::get_by_id`].
+    pub async fn get_movie_by_id(&self, id: &MovieId) -> Result<Movie, Error> {
+        self.get_by_id(id).await
+    }
+
+    /// Returns the quote with the given id. See [`Client::get_by_id`].
+    pub async fn get_quote_by_id(&self, id: &QuoteId) -> Result<Quote, Error> {
+        self.get_by_id(id).await
+    }
+
+    /// Returns the character with the given id. See [`Client::get_by_id`].
+    pub async fn get_character_by_id(&self, id: &CharacterId) -> Result<Character, Error> {
+        self.get_by_id(id).await
+    }
+
+    /// Returns the chapter with the given id. See [`Client::get_by_id`].
+    pub async fn get_chapter_by_id(&self, id: &ChapterId) -> Result<Chapter, Error> {
+        self.get_by_id(id).await
     }
 
-    /// Returns the movie with the given id.
+    /// Returns the chapters of the given book.
     ///
     /// # Errors
-    /// If there is no movie with the given id, an error is returned.
-    pub async fn get_movie_by_id(&self, id: &str) -> Result<Movie, Error> {
-        let url = format!("movie/{}", id);
-        let mut movies = self.request_with_url::<Movie>(&url).await?.get_contents();
-        movies
-            .pop()
-            .ok_or(Error::Other("No movie with id {} found".to_string()))
+    /// If the API doesn't know the book, an [`Error::NotFound`] is returned.
+    pub async fn get_chapters_from_book(&self, book_id: &BookId) -> Result<Vec<Chapter>, Error> {
+        let url = format!("book/{}/chapter", book_id);
+        Ok(self
+            .request_with_url::<Chapter>(&url)
+            .await
+            .map_err(|error| error.or_not_found(ItemType::Book, book_id))?
+            .get_contents())
     }
 
-    /// Returns the quote with the given id.
+    /// Returns the quotes of the given movie.
     ///
     /// # Errors
-    /// If there is no quote with the given id, an error is returned.
-    pub async fn get_quote_by_id(&self, id: &str) -> Result<Quote, Error> {
-        let url = format!("quote/{}", id);
-        let mut quotes = self.request_with_url::<Quote>(&url).await?.get_contents();
-        quotes
-            .pop()
-            .ok_or(Error::Other("No quote with id {} found".to_string()))
+    /// If the API doesn't know the movie, an [`Error::NotFound`] is returned.
+    pub async fn get_quotes_from_movie(&self, movie_id: &MovieId) -> Result<Vec<Quote>, Error> {
+        let url = format!("movie/{}/quote", movie_id);
+        Ok(self
+            .request_with_url::<Quote>(&url)
+            .await
+            .map_err(|error| error.or_not_found(ItemType::Movie, movie_id))?
+            .get_contents())
     }
 
-    /// Returns the character with the given id.
+    /// Returns the quotes of the given character.
     ///
     /// # Errors
-    /// If there is no character with the given id, an error is returned.
-    pub async fn get_character_by_id(&self, id: &str) -> Result<Character, Error> {
-        let url = format!("character/{}", id);
-        let mut characters = self
-            .request_with_url::<Character>(&url)
-            .await?
-            .get_contents();
-        characters
-            .pop()
-            .ok_or(Error::Other("No character with id {} found".to_string()))
+    /// If the API doesn't know the character, an [`Error::NotFound`] is returned.
+    pub async fn get_quotes_from_character(
+        &self,
+        character_id: &CharacterId,
+    ) -> Result<Vec<Quote>, Error> {
+        let url = format!("character/{}/quote", character_id);
+        Ok(self
+            .request_with_url::<Quote>(&url)
+            .await
+            .map_err(|error| error.or_not_found(ItemType::Character, character_id))?
+            .get_contents())
     }
 
-    /// Returns the chapter with the given id.
+    /// Returns the items with the objects they reference: the movie and the character
+    /// of each [`Quote`], or the book of each [`Chapter`].
+    ///
+    /// The referenced objects are fetched once per call, whatever the number of items
+    /// referencing them, with as few requests as possible ( one request per item type
+    /// for up to 100 ids ).
     ///
     /// # Errors
-    /// If there is no chapter with the given id, an error is returned.
-    pub async fn get_chapter_by_id(&self, id: &str) -> Result<Chapter, Error> {
-        let url = format!("chapter/{}", id);
-        let mut chapters = self.request_with_url::<Chapter>(&url).await?.get_contents();
-        chapters
-            .pop()
-            .ok_or(Error::Other("No chapter with id {} found".to_string()))
-    }
+    /// If a referenced object doesn't exist, an [`Error::NotFound`] is returned.
+    ///
+    /// # Examples
+    /// ```rust, no_run
+    /// use lotr_api::Client;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("your_token".to_string());
+    ///     let quotes = client.get_quotes().await.unwrap();
+    ///     for quote in client.hydrate(quotes).await.unwrap() {
+    ///         println!("{}: {:?}", quote.character.name, quote.quote.dialog);
+    ///     }
+    /// }
+    /// ```
+    pub async fn hydrate<T: Hydrate>(&self, items: Vec<T>) -> Result<Vec<T::Hydrated>, Error> {
+        let mut references = References::default();
+        for item in &items {
+            item.references(&mut references);
+        }
 
-    /// Returns the chapters of the given book.
-    pub async fn get_chapters_from_book(&self, book_id: &str) -> Result<Vec<Chapter>, Error> {
-        let url = format!("book/{}/chapter", book_id);
-        Ok(self.request_with_url::<Chapter>(&url).await?.get_contents())
-    }
+        let cache = Cache {
+            books: self.get_by_ids(references.books).await?,
+            movies: self.get_by_ids(references.movies).await?,
+            characters: self.get_by_ids(references.characters).await?,
+        };
 
-    /// Returns the quotes of the given book.
-    pub async fn get_quotes_from_movie(&self, movie_id: &str) -> Result<Vec<Quote>, Error> {
-        let url = format!("movie/{}/quote", movie_id);
-        Ok(self.request_with_url::<Quote>(&url).await?.get_contents())
+        items.into_iter().map(|item| item.hydrate(&cache)).collect()
     }
 
-    /// Returns the quotes of the given book.
-    pub async fn get_quotes_from_character(&self, character_id: &str) -> Result<Vec<Quote>, Error> {
-        let url = format!("character/{}/quote", character_id);
-        Ok(self.request_with_url::<Quote>(&url).await?.get_contents())
+    /// Returns the objects with the given ids, by id, fetched by batches.
+    async fn get_by_ids<T: ApiObject>(
+        &self,
+        ids: impl IntoIterator<Item = T::Id>,
+    ) -> Result<HashMap<T::Id, T>, Error> {
+        let ids: Vec<_> = ids.into_iter().collect();
+        let mut items = HashMap::with_capacity(ids.len());
+        for batch in ids.chunks(HYDRATION_BATCH_SIZE) {
+            let request = RequestBuilder::new(T::item_type())
+                .filter(Filter::Match(
+                    Attribute::id(&T::item_type()),
+                    Operator::Eq,
+                    batch.iter().cloned().map(FilterValue::id).collect(),
+                ))
+                .build()?;
+            let batch_items: Vec<T> = self.stream_typed(request).try_collect().await?;
+            items.extend(
+                batch_items
+                    .into_iter()
+                    .map(|item| (item.id().clone(), item)),
+            );
+        }
+        Ok(items)
     }
 
     /// returns the result of the given request.
@@ -202,6 +308,9 @@ impl Client {
 
     /// Returns the items of the given custom request.
     ///
+    /// The items are always decoded strictly: use [`Client::get_page`] to skip the
+    /// documents that can't be deserialized ( see [`DecodeMode`] ).
+    ///
     /// # Examples
     /// ```rust, no_run
     /// use lotr_api::{
@@ -219,7 +328,7 @@ impl Client {
     ///         .filter(Filter::Match(
     ///             Attribute::Book(BookAttribute::Name),
     ///             Operator::Eq,
-    ///             vec!["The Fellowship of the Ring".to_string()])
+    ///             vec!["The Fellowship of the Ring".into()])
     ///         )
     ///         .sort(Sort::new(SortOrder::Ascending, Attribute::Book(BookAttribute::Name)))
     ///         .build()
@@ -229,31 +338,464 @@ impl Client {
     /// }
     ///     
     pub async fn get(&self, request: Request) -> Result<Vec<Item>, Error> {
+        Ok(self
+            .fetch_page(request, DecodeMode::Strict)
+            .await?
+            .into_items())
+    }
+
+    /// Returns the items of the given typed request.
+    ///
+    /// Like for [`Client::get`], the items are always decoded strictly.
+    ///
+    /// # Examples
+    /// ```rust, no_run
+    /// use lotr_api::{
+    ///     attribute::CharacterAttribute,
+    ///     filter::{Filter, Operator},
+    ///     Character, Client, RequestBuilder};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("your_token".to_string());
+    ///     let request = RequestBuilder::<Character>::typed()
+    ///         .filter(Filter::Match(
+    ///             CharacterAttribute::Realm,
+    ///             Operator::Eq,
+    ///             vec!["Gondor".into()],
+    ///         ))
+    ///         .build()
+    ///         .expect("Failed to build request");
+    ///     let characters: Vec<Character> = client.get_typed(request).await.unwrap();
+    ///     // ...
+    /// }
+    /// ```
+    pub async fn get_typed<T: TypedItem>(&self, request: TypedRequest<T>) -> Result<Vec<T>, Error> {
+        Ok(self
+            .fetch_page_typed::<T>(request.into_request(), DecodeMode::Strict)
+            .await?
+            .into_items())
+    }
+
+    /// Returns the page of items of the given request, along with the pagination data.
+    /// See [`Page`].
+    ///
+    /// If the API can't run the whole request, because it is sorted by several attributes
+    /// or has filters that the API can't express ( see [`crate::request::plan::Plan`] ),
+    /// all the items that match the part the API supports are fetched, the client filters
+    /// and sorts them, and then applies the pagination of the request. The result is the
+    /// same as if the API had run the request, but every page fetches all the items.
+    ///
+    /// The documents are decoded with the [`DecodeMode`] of the client. In lenient mode,
+    /// the ones that can't be deserialized are reported by [`Page::skipped`].
+    pub async fn get_page(&self, request: Request) -> Result<Page<Item>, Error> {
+        self.fetch_page(request, self.decode_mode).await
+    }
+
+    /// Same as [`Client::get_page`], but the items are deserialized into the given type.
+    /// The type must match the item type of the request.
+    pub async fn get_page_typed<T>(&self, request: Request) -> Result<Page<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.fetch_page_typed(request, self.decode_mode).await
+    }
+
+    async fn fetch_page(&self, request: Request, mode: DecodeMode) -> Result<Page<Item>, Error> {
+        let page = match request.get_item_type() {
+            ItemType::Book => self
+                .fetch_page_typed::<Book>(request, mode)
+                .await?
+                .map(Item::from),
+            ItemType::Movie => self
+                .fetch_page_typed::<Movie>(request, mode)
+                .await?
+                .map(Item::from),
+            ItemType::Quote => self
+                .fetch_page_typed::<Quote>(request, mode)
+                .await?
+                .map(Item::from),
+            ItemType::Character => self
+                .fetch_page_typed::<Character>(request, mode)
+                .await?
+                .map(Item::from),
+            ItemType::Chapter => self
+                .fetch_page_typed::<Chapter>(request, mode)
+                .await?
+                .map(Item::from),
+        };
+        Ok(page)
+    }
+
+    async fn fetch_page_typed<T>(
+        &self,
+        request: Request,
+        mode: DecodeMode,
+    ) -> Result<Page<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let plan = request.plan();
+        if !plan.is_remote() {
+            return self.get_page_locally(request, plan, mode).await;
+        }
+        let url = request.get_url();
+        let response = self
+            .requester
+            .get_from_request::<Response<serde_json::Value>>(request.clone())
+            .await?;
+        Page::new(response, request).decode(mode, &url)
+    }
+
+    /// Fetches all the items that match the part of the request pushed down to the API,
+    /// applies the rest of the plan to them and returns the page asked by the request.
+    async fn get_page_locally<T>(
+        &self,
+        request: Request,
+        plan: Plan,
+        mode: DecodeMode,
+    ) -> Result<Page<T>, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let items = self.fetch_locally(&plan).await?;
+        let url = request.get_url();
+        Page::from_items(&items, request).decode(mode, &url)
+    }
+
+    /// Fetches all the items that match the part of the request pushed down to the API,
+    /// and returns the ones that match the rest of the plan, sorted.
+    async fn fetch_locally(&self, plan: &Plan) -> Result<Vec<serde_json::Value>, Error> {
+        let mut items = Vec::new();
+        let mut next = Some(plan.remote_request().clone());
+        while let Some(remote) = next {
+            let response = self
+                .requester
+                .get_from_request::<Response<serde_json::Value>>(remote.clone())
+                .await?;
+            let page = Page::new(response, remote);
+            next = page.next_request();
+            items.extend(page.into_items());
+        }
+        Ok(plan.apply(items))
+    }
+
+    /// Returns a stream over all the items of the given request. Unlike [`Client::get`],
+    /// which only returns one page, the stream keeps requesting the following pages
+    /// until the last one is reached.
+    ///
+    /// If the request has a pagination, the stream starts at the given page and
+    /// keeps its limit.
+    ///
+    /// The items are always decoded strictly, like for [`Client::get`].
+    ///
+    /// # Examples
+    /// ```rust, no_run
+    /// use futures::TryStreamExt;
+    /// use lotr_api::{Client, ItemType, RequestBuilder};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("your_token".to_string());
+    ///     let request = RequestBuilder::new(ItemType::Quote)
+    ///         .build()
+    ///         .expect("Failed to build request");
+    ///     let quotes: Vec<_> = client.stream(request).try_collect().await.unwrap();
+    ///     // ...
+    /// }
+    /// ```
+    pub fn stream(&self, request: Request) -> impl Stream<Item = Result<Item, Error>> + '_ {
         match request.get_item_type() {
-            ItemType::Book => {
-                let response = self.request::<Book>(request).await?;
-                Ok(response.into())
-            }
+            ItemType::Book => self
+                .stream_typed::<Book>(request)
+                .map_ok(Item::from)
+                .boxed(),
+            ItemType::Movie => self
+                .stream_typed::<Movie>(request)
+                .map_ok(Item::from)
+                .boxed(),
+            ItemType::Quote => self
+                .stream_typed::<Quote>(request)
+                .map_ok(Item::from)
+                .boxed(),
+            ItemType::Character => self
+                .stream_typed::<Character>(request)
+                .map_ok(Item::from)
+                .boxed(),
+            ItemType::Chapter => self
+                .stream_typed::<Chapter>(request)
+                .map_ok(Item::from)
+                .boxed(),
+        }
+    }
 
-            ItemType::Movie => {
-                let response = self.request::<Movie>(request).await?;
-                Ok(response.into())
-            }
+    /// Same as [`Client::stream`], but the items are deserialized into the given type.
+    /// The type must match the item type of the request.
+    ///
+    /// # Examples
+    /// ```rust, no_run
+    /// use futures::TryStreamExt;
+    /// use lotr_api::{Client, ItemType, Quote, RequestBuilder};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let client = Client::new("your_token".to_string());
+    ///     let request = RequestBuilder::new(ItemType::Quote)
+    ///         .build()
+    ///         .expect("Failed to build request");
+    ///     let quotes: Vec<Quote> = client.stream_typed(request).try_collect().await.unwrap();
+    ///     // ...
+    /// }
+    /// ```
+    pub fn stream_typed<T>(&self, request: Request) -> impl Stream<Item = Result<T, Error>> + '_
+    where
+        T: serde::de::DeserializeOwned + Send + 'static,
+    {
+        stream::try_unfold((Some(request), None), move |(request, local)| async move {
+            let Some(request) = request else {
+                return Ok::<_, Error>(None);
+            };
+            let plan = request.plan();
+            let (page, local) = if plan.is_remote() {
+                let page = self
+                    .fetch_page_typed::<T>(request, DecodeMode::Strict)
+                    .await?;
+                (page, local)
+            } else {
+                // The items are fetched and evaluated once, then paged over in memory.
+                let items = match local {
+                    Some(items) => items,
+                    None => self.fetch_locally(&plan).await?,
+                };
+                let url = request.get_url();
+                let page = Page::from_items(&items, request).decode(DecodeMode::Strict, &url)?;
+                (page, Some(items))
+            };
+            let next = page.next_request();
+            let docs = page.into_items().into_iter().map(Ok::<T, Error>);
+            Ok(Some((stream::iter(docs), (next, local))))
+        })
+        .try_flatten()
+    }
+}
 
-            ItemType::Quote => {
-                let response = self.request::<Quote>(request).await?;
-                Ok(response.into())
-            }
+/// This struct is used to build a [`Client`].
+///
+/// It allows to point the client to another url ( a local mirror or a mock of the API ),
+/// to set the timeouts and the headers of the requests, or to provide an already
+/// configured [`reqwest::Client`] or a whole other [`Transport`].
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use lotr_api::ClientBuilder;
+///
+/// let client = ClientBuilder::new("your_token".to_string())
+///     .base_url("http://localhost:8080/v2")
+///     .connect_timeout(Duration::from_secs(5))
+///     .read_timeout(Duration::from_secs(30))
+///     .user_agent("my-app/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    token: String,
+    base_url: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    http_client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
+    rate_limit: Option<RateLimit>,
+    retry_policy: RetryPolicy,
+    decode_mode: DecodeMode,
+}
 
-            ItemType::Character => {
-                let response = self.request::<Character>(request).await?;
-                Ok(response.into())
-            }
+impl ClientBuilder {
+    /// Creates a new builder with the given token and the default settings.
+    pub fn new(token: String) -> Self {
+        Self {
+            token,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            connect_timeout: None,
+            read_timeout: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            http_client: None,
+            transport: None,
+            rate_limit: None,
+            retry_policy: RetryPolicy::default(),
+            decode_mode: DecodeMode::default(),
+        }
+    }
+
+    /// Sets the url that every request is relative to.
+    /// By default it is [`DEFAULT_BASE_URL`].
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sets the timeout for the connection to the API.
+    ///
+    /// It is ignored if a client is provided with [`ClientBuilder::http_client`].
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for each read operation of a response.
+    ///
+    /// It is ignored if a client is provided with [`ClientBuilder::http_client`].
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header of the requests.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets headers that are sent with every request.
+    /// The `Accept` and `Authorization` headers are always set by the client.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Uses the given [`reqwest::Client`] to make the requests instead of creating a new one.
+    /// This is useful to share a connection pool with the rest of an application.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Sends the requests through the given [`Transport`] instead of a [`reqwest::Client`].
+    /// The timeouts and the client set with [`ClientBuilder::http_client`] are then ignored.
+    ///
+    /// See [`crate::transport::FixtureTransport`] to test code that uses the client offline.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
 
-            ItemType::Chapter => {
-                let response = self.request::<Chapter>(request).await?;
-                Ok(response.into())
+    /// Limits the number of requests the client makes. The limit is shared by
+    /// all the clones of the client. See [`RateLimit`].
+    ///
+    /// By default there is no limit. [`ClientBuilder::build`] fails with an
+    /// [`Error::InvalidRateLimit`] if the budget or the window is zero.
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Sets how failed requests are retried. See [`RetryPolicy`].
+    ///
+    /// By default requests are retried up to three times, use
+    /// [`RetryPolicy::none`] to disable the retries.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets how the documents that can't be deserialized are handled. See [`DecodeMode`].
+    ///
+    /// By default they make the request fail. With [`DecodeMode::Lenient`] they are skipped,
+    /// and reported by [`Page::skipped`]. Only [`Client::get_page`] and
+    /// [`Client::get_page_typed`] use the mode: the other methods have nowhere to report
+    /// the skipped documents, so they always decode strictly.
+    pub fn decode_mode(mut self, decode_mode: DecodeMode) -> Self {
+        self.decode_mode = decode_mode;
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    /// If the token or the user agent can't be used as a header value, if the
+    /// rate limit has no budget or no window, or if the [`reqwest::Client`] can't be created.
+    pub fn build(self) -> Result<Client, Error> {
+        let mut headers = self.default_headers;
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        let mut authorization = HeaderValue::from_str(&format!("Bearer {}", self.token))
+            .map_err(|_| Error::Other("Invalid token".to_string()))?;
+        authorization.set_sensitive(true);
+        headers.insert(header::AUTHORIZATION, authorization);
+        if let Some(user_agent) = &self.user_agent {
+            let user_agent = HeaderValue::from_str(user_agent)
+                .map_err(|_| Error::Other(format!("Invalid user agent: {}", user_agent)))?;
+            headers.insert(header::USER_AGENT, user_agent);
+        }
+
+        let transport: Arc<dyn Transport> = match (self.transport, self.http_client) {
+            (Some(transport), _) => transport,
+            (None, Some(client)) => Arc::new(ReqwestTransport::new(client)),
+            (None, None) => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.read_timeout {
+                    builder = builder.read_timeout(timeout);
+                }
+                Arc::new(ReqwestTransport::new(builder.build()?))
             }
+        };
+
+        let mut base_url = self.base_url;
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+
+        let mut requester =
+            Requester::new(transport, base_url, headers).with_retry_policy(self.retry_policy);
+        if let Some(rate_limit) = self.rate_limit {
+            rate_limit.validate()?;
+            requester = requester.with_rate_limiter(RateLimiter::new(rate_limit));
+        }
+
+        Ok(Client {
+            requester,
+            decode_mode: self.decode_mode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_client() {
+        let client = ClientBuilder::new("token".to_string())
+            .base_url("http://localhost:8080/v2")
+            .connect_timeout(Duration::from_secs(1))
+            .read_timeout(Duration::from_secs(1))
+            .user_agent("lotr-api-tests")
+            .build();
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_build_client_invalid_token() {
+        let client = ClientBuilder::new("invalid\ntoken".to_string()).build();
+        assert!(client.is_err());
+    }
+
+    #[test]
+    fn test_build_client_invalid_rate_limit() {
+        for (budget, window) in [(0, Duration::from_secs(60)), (10, Duration::ZERO)] {
+            let client = ClientBuilder::new("token".to_string())
+                .rate_limit(RateLimit::new(budget, window))
+                .build();
+            assert!(matches!(
+                client,
+                Err(Error::InvalidRateLimit { budget: b, window: w }) if b == budget && w == window
+            ));
         }
     }
 }
@@ -1,5 +1,19 @@
 //! Definition of the Error type for the crate.
 
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::{
+    attribute::Attribute,
+    filter::{FilterValue, Operator, ValueKind},
+    request::GetUrl,
+    ItemType,
+};
+
+/// Maximum number of characters of a response body that are kept in [`Error::Decode`].
+const BODY_SNIPPET_LENGTH: usize = 200;
+
 /// The error type for this crate.
 /// It is used to harmonize the error types of the dependencies and to add some custom errors.
 #[derive(Debug)]
@@ -11,10 +25,149 @@ pub enum Error {
     InvalidSort,
     InvalidFilter,
     InvalidSecondaryItemType,
+    /// The API has no route for the items of the secondary item type that belong to an
+    /// item of the item type, like `book/{id}/movie`.
+    UnsupportedSecondaryItemType {
+        item_type: ItemType,
+        secondary_item_type: ItemType,
+    },
+    /// The regular expression of a [`crate::filter::Filter::Regex`] is not valid.
+    InvalidRegex {
+        pattern: String,
+        reason: String,
+    },
+    /// A url could not be parsed into a [`crate::Request`].
+    InvalidUrl {
+        url: String,
+        reason: String,
+    },
+    /// The API can't express the filter, which can only be evaluated by the client.
+    /// It holds the filter.
+    UnsupportedFilter(String),
+    /// A serialized request was saved with a format version this crate does not support.
+    UnsupportedVersion(u32),
+    /// The operator compares numbers, but the attribute is not a number.
+    InvalidOperator {
+        attribute: Attribute,
+        operator: Operator,
+    },
+    /// The operator of a filter can't be used with this number of values: a filter
+    /// needs at least one value, and a comparison exactly one.
+    InvalidValueCount {
+        attribute: Attribute,
+        operator: Operator,
+        count: usize,
+    },
+    /// A number of a filter is NaN or infinite.
+    InvalidNumber(f64),
+    /// The value of a filter is not of the kind of the attribute.
+    InvalidFilterValue {
+        attribute: Attribute,
+        value: FilterValue,
+        expected: ValueKind,
+    },
+    /// The id is not made of the 24 hexadecimal digits of an ObjectId.
+    InvalidId(String),
+    /// The id of a request belongs to another item type than the one of the request.
+    MismatchedId {
+        expected: ItemType,
+        found: ItemType,
+    },
+    /// There is no item type with the given name.
+    UnknownItemType(String),
+    /// The item type has no attribute with the given name.
+    UnknownAttribute {
+        item_type: ItemType,
+        name: String,
+    },
+    /// There is no item of the given type with the given id.
+    NotFound {
+        item_type: ItemType,
+        id: String,
+    },
+    /// The API rejected the token.
+    Unauthorized,
+    /// The budget or the window of a [`crate::RateLimit`] is zero, so no request
+    /// could ever be made.
+    InvalidRateLimit {
+        budget: u32,
+        window: Duration,
+    },
+    /// The rate limit was reached, either the one of the client or the one of the API.
+    /// `retry_after` is how long to wait before trying again, if known.
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    /// The API failed with a server error ( 5xx status ).
+    Server {
+        status: StatusCode,
+        body: String,
+    },
+    /// The API answered with an unsuccessful status that has no dedicated variant.
+    UnexpectedStatus {
+        status: StatusCode,
+        body: String,
+    },
+    /// The response of the given url could not be deserialized.
+    /// `body_snippet` contains the beginning of the response.
+    Decode {
+        url: String,
+        body_snippet: String,
+        source: serde_json::Error,
+    },
     Other(String),
 }
 
-impl std::error::Error for Error {}
+impl Error {
+    /// Creates the error that corresponds to an unsuccessful response.
+    pub(crate) fn from_status(
+        status: StatusCode,
+        retry_after: Option<Duration>,
+        body: String,
+    ) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { retry_after },
+            status if status.is_server_error() => Self::Server { status, body },
+            status => Self::UnexpectedStatus { status, body },
+        }
+    }
+
+    /// Turns a `404 Not Found` answered for the item of the given type with the
+    /// given id into an [`Error::NotFound`], leaving the other errors as they are.
+    pub(crate) fn or_not_found(self, item_type: ItemType, id: impl std::fmt::Display) -> Self {
+        match self {
+            Self::UnexpectedStatus {
+                status: StatusCode::NOT_FOUND,
+                ..
+            } => Self::NotFound {
+                item_type,
+                id: id.to_string(),
+            },
+            error => error,
+        }
+    }
+
+    /// Creates the error for a response body that could not be deserialized.
+    pub(crate) fn decode(url: &str, body: &str, source: serde_json::Error) -> Self {
+        Self::Decode {
+            url: url.to_string(),
+            body_snippet: body.chars().take(BODY_SNIPPET_LENGTH).collect(),
+            source,
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::SerdeJson(error) => Some(error),
+            Self::Reqwest(error) => Some(error),
+            Self::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -24,6 +177,115 @@ impl std::fmt::Display for Error {
             Self::InvalidSort => write!(formatter, "Invalid sort"),
             Self::InvalidFilter => write!(formatter, "Invalid filter"),
             Self::InvalidSecondaryItemType => write!(formatter, "Invalid secondary item type"),
+            Self::UnsupportedSecondaryItemType {
+                item_type,
+                secondary_item_type,
+            } => write!(
+                formatter,
+                "Unsupported secondary item type: {}/{{id}}/{} is not a route of the API",
+                item_type, secondary_item_type
+            ),
+            Self::InvalidRegex { pattern, reason } => {
+                write!(formatter, "Invalid regex /{}/: {}", pattern, reason)
+            }
+            Self::InvalidUrl { url, reason } => {
+                write!(formatter, "Invalid url {}: {}", url, reason)
+            }
+            Self::UnsupportedFilter(filter) => {
+                write!(formatter, "The API can't express the filter {}", filter)
+            }
+            Self::UnsupportedVersion(version) => {
+                write!(formatter, "Unsupported request format version {}", version)
+            }
+            Self::InvalidOperator {
+                attribute,
+                operator,
+            } => write!(
+                formatter,
+                "The operator {} can't be used with {}, which is not a number",
+                operator.get_url(),
+                attribute.get_url()
+            ),
+            Self::InvalidValueCount {
+                attribute,
+                operator,
+                count,
+            } => write!(
+                formatter,
+                "The filter {}{} has {} values",
+                attribute.get_url(),
+                operator.get_url(),
+                count
+            ),
+            Self::InvalidNumber(number) => {
+                write!(
+                    formatter,
+                    "Invalid number {}, expected a finite number",
+                    number
+                )
+            }
+            Self::InvalidFilterValue {
+                attribute,
+                value,
+                expected,
+            } => write!(
+                formatter,
+                "Invalid value {} ( {} ) for {}, expected a {}",
+                value,
+                value.kind(),
+                attribute.get_url(),
+                expected
+            ),
+            Self::InvalidId(id) => {
+                write!(
+                    formatter,
+                    "Invalid id {}, expected 24 hexadecimal digits",
+                    id
+                )
+            }
+            Self::MismatchedId { expected, found } => write!(
+                formatter,
+                "Expected the id of a {}, found the id of a {}",
+                expected, found
+            ),
+            Self::UnknownItemType(name) => write!(formatter, "Unknown item type {}", name),
+            Self::UnknownAttribute { item_type, name } => {
+                write!(
+                    formatter,
+                    "The {} item has no attribute {}",
+                    item_type, name
+                )
+            }
+            Self::NotFound { item_type, id } => {
+                write!(formatter, "No {} with id {} found", item_type, id)
+            }
+            Self::Unauthorized => write!(formatter, "Unauthorized, check your token"),
+            Self::InvalidRateLimit { budget, window } => write!(
+                formatter,
+                "Invalid rate limit: {} requests every {:?}, both must be greater than zero",
+                budget, window
+            ),
+            Self::RateLimited { retry_after } => match retry_after {
+                Some(retry_after) => write!(
+                    formatter,
+                    "Rate limit reached, retry after {:?}",
+                    retry_after
+                ),
+                None => write!(formatter, "Rate limit reached"),
+            },
+            Self::Server { status, body } => write!(formatter, "Server error {}: {}", status, body),
+            Self::UnexpectedStatus { status, body } => {
+                write!(formatter, "Unexpected status {}: {}", status, body)
+            }
+            Self::Decode {
+                url,
+                body_snippet,
+                source,
+            } => write!(
+                formatter,
+                "Failed to decode the response of {}: {} ( response: {} )",
+                url, source, body_snippet
+            ),
             Self::Other(message) => write!(formatter, "{}", message),
         }
     }
@@ -40,3 +302,55 @@ impl From<serde_json::Error> for Error {
         Self::SerdeJson(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        assert!(matches!(
+            Error::from_status(StatusCode::UNAUTHORIZED, None, String::new()),
+            Error::Unauthorized
+        ));
+        assert!(matches!(
+            Error::from_status(
+                StatusCode::TOO_MANY_REQUESTS,
+                Some(Duration::from_secs(3)),
+                String::new()
+            ),
+            Error::RateLimited {
+                retry_after: Some(_)
+            }
+        ));
+        assert!(matches!(
+            Error::from_status(StatusCode::BAD_GATEWAY, None, "Bad gateway".to_string()),
+            Error::Server {
+                status: StatusCode::BAD_GATEWAY,
+                ..
+            }
+        ));
+        assert!(matches!(
+            Error::from_status(StatusCode::NOT_FOUND, None, String::new()),
+            Error::UnexpectedStatus {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_decode_snippet() {
+        let body = "x".repeat(1000);
+        let source = serde_json::from_str::<u32>(&body).unwrap_err();
+        match Error::decode("book", &body, source) {
+            Error::Decode {
+                url, body_snippet, ..
+            } => {
+                assert_eq!(url, "book");
+                assert_eq!(body_snippet.len(), BODY_SNIPPET_LENGTH);
+            }
+            _ => panic!("Expected a decode error"),
+        }
+    }
+}
@@ -1,8 +1,14 @@
+use serde::{Deserialize, Serialize};
+
 use crate::ItemType;
 
 /// The different attributes that can be used to sort the different items that can be retrieved
 /// from the API. The contain all the attributes that are available for the different items.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+///
+/// They are serialized with the item type as the key and the name used in the urls
+/// as the value, like `{"book": "name"}`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Attribute {
     Book(BookAttribute),
     Movie(MovieAttribute),
@@ -23,6 +29,36 @@ impl From<Attribute> for ItemType {
     }
 }
 
+impl From<BookAttribute> for Attribute {
+    fn from(attribute: BookAttribute) -> Self {
+        Attribute::Book(attribute)
+    }
+}
+
+impl From<MovieAttribute> for Attribute {
+    fn from(attribute: MovieAttribute) -> Self {
+        Attribute::Movie(attribute)
+    }
+}
+
+impl From<QuoteAttribute> for Attribute {
+    fn from(attribute: QuoteAttribute) -> Self {
+        Attribute::Quote(attribute)
+    }
+}
+
+impl From<CharacterAttribute> for Attribute {
+    fn from(attribute: CharacterAttribute) -> Self {
+        Attribute::Character(attribute)
+    }
+}
+
+impl From<ChapterAttribute> for Attribute {
+    fn from(attribute: ChapterAttribute) -> Self {
+        Attribute::Chapter(attribute)
+    }
+}
+
 impl Attribute {
     pub(crate) fn get_item_type(&self) -> ItemType {
         match self {
@@ -35,14 +71,18 @@ impl Attribute {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum BookAttribute {
+    #[serde(rename = "_id")]
     Id,
     Name,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum MovieAttribute {
+    #[serde(rename = "_id")]
     Id,
     Name,
     RuntimeInMinutes,
@@ -53,18 +93,23 @@ pub enum MovieAttribute {
     RottenTomatoesScore,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum QuoteAttribute {
+    #[serde(rename = "_id")]
     Id,
     Dialog,
     Movie,
     Character,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum CharacterAttribute {
+    #[serde(rename = "_id")]
     Id,
     Height,
+    Race,
     Gender,
     Birth,
     Spouse,
@@ -75,8 +120,10 @@ pub enum CharacterAttribute {
     WikiUrl,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ChapterAttribute {
+    #[serde(rename = "_id")]
     Id,
     ChapterName,
     Book,
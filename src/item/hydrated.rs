@@ -0,0 +1,138 @@
+//! The items with the objects they reference.
+//!
+//! A [`Quote`] only holds the ids of its movie and of its character, and a [`Chapter`]
+//! the id of its book. [`crate::Client::hydrate`] fetches the referenced objects and
+//! returns them alongside the items, as a [`HydratedQuote`] or a [`HydratedChapter`].
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{Error, ItemType};
+
+use super::{
+    id::{BookId, CharacterId, MovieId},
+    object::{Book, Chapter, Character, Movie, Quote},
+    TypedItem,
+};
+
+/// A [`Quote`] with its movie and its character.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydratedQuote {
+    pub quote: Quote,
+    pub movie: Movie,
+    pub character: Character,
+}
+
+/// A [`Chapter`] with its book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydratedChapter {
+    pub chapter: Chapter,
+    pub book: Book,
+}
+
+/// This trait is implemented by the objects that reference other objects,
+/// which can be hydrated with [`crate::Client::hydrate`].
+///
+/// It is sealed: only the objects of this crate implement it.
+pub trait Hydrate: TypedItem + private::Sealed {
+    /// The object with the objects it references.
+    type Hydrated;
+
+    /// Adds the ids of the objects referenced by the object.
+    fn references(&self, references: &mut References);
+
+    /// Returns the object with the objects it references, taken from the cache.
+    ///
+    /// # Errors
+    /// If one of the referenced objects is not in the cache, an [`Error::NotFound`] is returned.
+    fn hydrate(self, cache: &Cache) -> Result<Self::Hydrated, Error>;
+}
+
+impl Hydrate for Quote {
+    type Hydrated = HydratedQuote;
+
+    fn references(&self, references: &mut References) {
+        references.movies.insert(self.movie.clone());
+        references.characters.insert(self.character.clone());
+    }
+
+    fn hydrate(self, cache: &Cache) -> Result<Self::Hydrated, Error> {
+        let movie = cache.movie(&self.movie).ok_or_else(|| Error::NotFound {
+            item_type: ItemType::Movie,
+            id: self.movie.to_string(),
+        })?;
+        let character = cache
+            .character(&self.character)
+            .ok_or_else(|| Error::NotFound {
+                item_type: ItemType::Character,
+                id: self.character.to_string(),
+            })?;
+        Ok(HydratedQuote {
+            movie: movie.clone(),
+            character: character.clone(),
+            quote: self,
+        })
+    }
+}
+
+impl Hydrate for Chapter {
+    type Hydrated = HydratedChapter;
+
+    fn references(&self, references: &mut References) {
+        references.books.insert(self.book.clone());
+    }
+
+    fn hydrate(self, cache: &Cache) -> Result<Self::Hydrated, Error> {
+        let book = cache.book(&self.book).ok_or_else(|| Error::NotFound {
+            item_type: ItemType::Book,
+            id: self.book.to_string(),
+        })?;
+        Ok(HydratedChapter {
+            book: book.clone(),
+            chapter: self,
+        })
+    }
+}
+
+mod private {
+    use crate::{Chapter, Quote};
+
+    /// Prevents [`super::Hydrate`] from being implemented outside of this crate.
+    pub trait Sealed {}
+
+    impl Sealed for Quote {}
+    impl Sealed for Chapter {}
+}
+
+/// The ids of the objects referenced by the objects that are hydrated.
+/// Each id is only kept once, so that each object is only fetched once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct References {
+    pub(crate) books: BTreeSet<BookId>,
+    pub(crate) movies: BTreeSet<MovieId>,
+    pub(crate) characters: BTreeSet<CharacterId>,
+}
+
+/// The objects fetched while hydrating, by id.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cache {
+    pub(crate) books: HashMap<BookId, Book>,
+    pub(crate) movies: HashMap<MovieId, Movie>,
+    pub(crate) characters: HashMap<CharacterId, Character>,
+}
+
+impl Cache {
+    /// Returns the book with the given id, if it was fetched.
+    pub fn book(&self, id: &BookId) -> Option<&Book> {
+        self.books.get(id)
+    }
+
+    /// Returns the movie with the given id, if it was fetched.
+    pub fn movie(&self, id: &MovieId) -> Option<&Movie> {
+        self.movies.get(id)
+    }
+
+    /// Returns the character with the given id, if it was fetched.
+    pub fn character(&self, id: &CharacterId) -> Option<&Character> {
+        self.characters.get(id)
+    }
+}
@@ -0,0 +1,164 @@
+//! The ids of the items.
+//!
+//! Each item type has its own id type, so that the id of a movie can't be used to
+//! get a character. The ids are the 24 hexadecimal digits of a MongoDB ObjectId,
+//! like `5cd95395de30eff6ebccde5d`, and they are serialized as plain strings.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, ItemType};
+
+/// The number of hexadecimal digits of an id.
+const ID_LENGTH: usize = 24;
+
+/// This trait is implemented by the ids of the items.
+pub trait Id:
+    Clone + Eq + std::hash::Hash + fmt::Display + FromStr<Err = Error> + Into<String>
+{
+    /// Returns the item type of the items identified by the id.
+    fn item_type() -> ItemType;
+
+    /// Returns the id as a string.
+    fn as_str(&self) -> &str;
+}
+
+/// Checks that the id is a valid ObjectId.
+pub(crate) fn validate(id: &str) -> Result<(), Error> {
+    if id.len() == ID_LENGTH && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(Error::InvalidId(id.to_string()))
+    }
+}
+
+macro_rules! id {
+    ($(#[$doc:meta])* $name:ident, $item_type:expr) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates the id from its string.
+            ///
+            /// # Errors
+            /// If the string is not made of 24 hexadecimal digits, an [`Error::InvalidId`] is returned.
+            pub fn new(id: impl Into<String>) -> Result<Self, Error> {
+                let id = id.into();
+                validate(&id)?;
+                Ok(Self(id))
+            }
+        }
+
+        impl Id for $name {
+            fn item_type() -> ItemType {
+                $item_type
+            }
+
+            fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                Self::new(id)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = Error;
+
+            fn try_from(id: String) -> Result<Self, Self::Error> {
+                Self::new(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+    };
+}
+
+id!(
+    /// The id of a [`crate::Book`].
+    BookId,
+    ItemType::Book
+);
+id!(
+    /// The id of a [`crate::Movie`].
+    MovieId,
+    ItemType::Movie
+);
+id!(
+    /// The id of a [`crate::Quote`].
+    QuoteId,
+    ItemType::Quote
+);
+id!(
+    /// The id of a [`crate::Character`].
+    CharacterId,
+    ItemType::Character
+);
+id!(
+    /// The id of a [`crate::Chapter`].
+    ChapterId,
+    ItemType::Chapter
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_id() {
+        let id: MovieId = "5cd95395de30eff6ebccde5d".parse().unwrap();
+        assert_eq!(id.as_str(), "5cd95395de30eff6ebccde5d");
+        assert_eq!(id.to_string(), "5cd95395de30eff6ebccde5d");
+        assert_eq!(MovieId::item_type(), ItemType::Movie);
+        assert_eq!(
+            serde_json::to_string(&id).unwrap(),
+            r#""5cd95395de30eff6ebccde5d""#
+        );
+        assert_eq!(
+            serde_json::from_str::<MovieId>(r#""5cd95395de30eff6ebccde5d""#).unwrap(),
+            id
+        );
+    }
+
+    #[test]
+    fn test_invalid_id() {
+        for id in [
+            "",
+            "123",
+            "5cd95395de30eff6ebccde5",
+            "5cd95395de30eff6ebccde5d0",
+            "5cd95395de30eff6ebccde5g",
+        ] {
+            assert!(
+                matches!(BookId::new(id), Err(Error::InvalidId(ref invalid)) if invalid == id),
+                "{}",
+                id
+            );
+        }
+        assert!(serde_json::from_str::<BookId>(r#""123""#).is_err());
+    }
+}
@@ -0,0 +1,811 @@
+//! Structured values parsed from the free-form fields of a [`Character`].
+//!
+//! The API returns the height, the birth and the death of the characters as they are
+//! written in the wiki, like `198cm (6'6")`, `March 1 ,TA 2931` or `Late ,Third Age`.
+//! [`Character::height`], [`Character::birth`] and [`Character::death`] keep those strings,
+//! and the accessors of this module parse them into a [`Height`] and [`Date`]s.
+//!
+//! The gender, the realm and the hair of the characters are not written consistently
+//! either ( `Male`, `male`, `NaN`... ), so they are normalized into a [`Gender`], a [`Realm`]
+//! and a [`Hair`], which keep the values they don't know in their `Other` variant. Their
+//! `filter_eq` and `filter_ne` constructors match all the spellings of the values, like
+//! [`Gender::filter_eq`].
+
+use std::{cmp::Ordering, fmt, sync::OnceLock};
+
+use regex::Regex;
+
+use crate::{
+    attribute::CharacterAttribute,
+    filter::{Filter, FilterValue, Operator},
+};
+
+use super::object::Character;
+
+/// The number of centimeters in an inch.
+const CENTIMETERS_PER_INCH: f32 = 2.54;
+
+/// The height of a character.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Height {
+    centimeters: f32,
+}
+
+impl Height {
+    /// Creates a height from its value in centimeters.
+    pub fn from_centimeters(centimeters: f32) -> Self {
+        Self { centimeters }
+    }
+
+    /// Creates a height from its value in feet and inches.
+    pub fn from_feet_and_inches(feet: f32, inches: f32) -> Self {
+        Self::from_centimeters((feet * 12.0 + inches) * CENTIMETERS_PER_INCH)
+    }
+
+    /// Returns the height in centimeters.
+    pub fn centimeters(&self) -> f32 {
+        self.centimeters
+    }
+
+    /// Returns the height in meters.
+    pub fn meters(&self) -> f32 {
+        self.centimeters / 100.0
+    }
+
+    /// Returns the height in inches.
+    pub fn inches(&self) -> f32 {
+        self.centimeters / CENTIMETERS_PER_INCH
+    }
+
+    /// Parses the height as written in the API, preferring the metric value when
+    /// both are given, like in `1.06m (3'6")`. Returns `None` if there is no height.
+    pub fn parse(height: &str) -> Option<Self> {
+        static METRIC: OnceLock<Regex> = OnceLock::new();
+        static IMPERIAL: OnceLock<Regex> = OnceLock::new();
+        let metric = METRIC.get_or_init(|| Regex::new(r"(\d+(?:\.\d+)?)\s*(cm|m)\b").unwrap());
+        let imperial = IMPERIAL.get_or_init(|| {
+            Regex::new(r#"(\d+(?:\.\d+)?)\s*(?:'|ft)\s*(?:(\d+(?:\.\d+)?)\s*(?:"|in))?"#).unwrap()
+        });
+
+        if let Some(captures) = metric.captures(height) {
+            let value: f32 = captures[1].parse().ok()?;
+            return Some(match &captures[2] {
+                "cm" => Self::from_centimeters(value),
+                _ => Self::from_centimeters(value * 100.0),
+            });
+        }
+        let captures = imperial.captures(height)?;
+        let feet: f32 = captures[1].parse().ok()?;
+        let inches: f32 = match captures.get(2) {
+            Some(inches) => inches.as_str().parse().ok()?,
+            None => 0.0,
+        };
+        Some(Self::from_feet_and_inches(feet, inches))
+    }
+}
+
+impl fmt::Display for Height {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:.0}cm", self.centimeters)
+    }
+}
+
+/// The ages of Middle-earth, in chronological order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Age {
+    /// The Years of the Trees ( `YT` ), before the First Age.
+    YearsOfTheTrees,
+    /// The First Age ( `FA` ).
+    First,
+    /// The Second Age ( `SA` ).
+    Second,
+    /// The Third Age ( `TA` ).
+    Third,
+    /// The Fourth Age ( `FO` ).
+    Fourth,
+}
+
+impl Age {
+    /// Returns the age of the abbreviation used by the API, like `TA`.
+    fn from_abbreviation(abbreviation: &str) -> Option<Self> {
+        match abbreviation {
+            "YT" => Some(Age::YearsOfTheTrees),
+            "FA" => Some(Age::First),
+            "SA" => Some(Age::Second),
+            "TA" => Some(Age::Third),
+            "FO" => Some(Age::Fourth),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of years of the age, if it is known.
+    fn length(&self) -> Option<u32> {
+        match self {
+            Age::First => Some(590),
+            Age::Second => Some(3441),
+            Age::Third => Some(3021),
+            Age::YearsOfTheTrees | Age::Fourth => None,
+        }
+    }
+
+    /// Returns the age of its name, like `third`.
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "first" => Some(Age::First),
+            "second" => Some(Age::Second),
+            "third" => Some(Age::Third),
+            "fourth" => Some(Age::Fourth),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Age {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let abbreviation = match self {
+            Age::YearsOfTheTrees => "YT",
+            Age::First => "FA",
+            Age::Second => "SA",
+            Age::Third => "TA",
+            Age::Fourth => "FO",
+        };
+        write!(formatter, "{}", abbreviation)
+    }
+}
+
+/// The part of an age a date without a year falls in, like in `Late ,Third Age`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Period {
+    /// The first third of the age.
+    Early,
+    /// The second third of the age.
+    Mid,
+    /// The last third of the age.
+    Late,
+}
+
+impl Period {
+    /// Returns the period named at the start of a date, like `late`.
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        if prefix.contains("early") {
+            Some(Period::Early)
+        } else if prefix.contains("mid") {
+            Some(Period::Mid)
+        } else if prefix.contains("late") {
+            Some(Period::Late)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the period of the given year of the age, or `None` if the length
+    /// of the age is not known.
+    fn of_year(age: Age, year: u32) -> Option<Self> {
+        let length = age.length()?;
+        // In u64, so that the years that the API may get wrong don't overflow.
+        Some(
+            match u64::from(year.saturating_sub(1)) * 3 / u64::from(length) {
+                0 => Period::Early,
+                1 => Period::Mid,
+                _ => Period::Late,
+            },
+        )
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Period::Early => "Early",
+            Period::Mid => "Mid",
+            Period::Late => "Late",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// A date of Middle-earth, like the birth or the death of a character.
+///
+/// Dates are ordered chronologically: first by age, then by their [`Period`] in the age,
+/// then by year. A year falls in the period of its third of the age, or in the middle of
+/// the ages whose length is not known ( the Years of the Trees and the Fourth Age ). A date
+/// without a year comes before the dates of its period that have one, and a date with
+/// neither a year nor a period before all the dates of its age. The [`Date::Unknown`]
+/// dates come last.
+///
+/// # Example
+/// ```
+/// use lotr_api::lore::{Age, Date, Period};
+///
+/// assert_eq!(
+///     Date::parse("March 1 ,TA 2931"),
+///     Date::Known {
+///         age: Age::Third,
+///         year: Some(2931),
+///         period: None,
+///         approximate: false,
+///         uncertain: false,
+///     }
+/// );
+/// assert_eq!(
+///     Date::parse("Late ,Third Age"),
+///     Date::Known {
+///         age: Age::Third,
+///         year: None,
+///         period: Some(Period::Late),
+///         approximate: true,
+///         uncertain: false,
+///     }
+/// );
+/// assert_eq!(Date::parse("NaN"), Date::Unknown);
+///
+/// assert!(Date::parse("SA 3441") < Date::parse("TA 241"));
+/// assert!(Date::parse("TA 241") < Date::parse("Late ,Third Age"));
+/// assert!(Date::parse("Late ,Third Age") < Date::parse("TA 2931"));
+/// assert!(Date::parse("FO 120") < Date::parse("Unknown"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Date {
+    /// A date in one of the ages.
+    Known {
+        age: Age,
+        /// The year in the age, if it is known.
+        year: Option<u32>,
+        /// The part of the age named by the date, like `Late` in `Late ,Third Age`.
+        period: Option<Period>,
+        /// The date is not exact, like `Late ,Third Age` or `c. TA 2900`.
+        approximate: bool,
+        /// The sources don't agree on the date, like `TA 3019 (possibly)`.
+        uncertain: bool,
+    },
+    /// The date is unknown, or couldn't be parsed.
+    Unknown,
+}
+
+impl Date {
+    /// Parses the date as written in the API. Anything that doesn't name an age,
+    /// or that starts with `Unknown`, is a [`Date::Unknown`].
+    pub fn parse(date: &str) -> Self {
+        static ABBREVIATION: OnceLock<Regex> = OnceLock::new();
+        static NAME: OnceLock<Regex> = OnceLock::new();
+        let abbreviation =
+            ABBREVIATION.get_or_init(|| Regex::new(r"\b(YT|FA|SA|TA|FO)\b\s*(\d+)?").unwrap());
+        let name =
+            NAME.get_or_init(|| Regex::new(r"(?i)\b(first|second|third|fourth)\s+age\b").unwrap());
+
+        let lowercase = date.trim().to_lowercase();
+        if lowercase.starts_with("unknown") {
+            return Date::Unknown;
+        }
+
+        let (age, year, start) = if let Some(captures) = abbreviation.captures(date) {
+            let age = Age::from_abbreviation(&captures[1]);
+            let year = captures.get(2).and_then(|year| year.as_str().parse().ok());
+            (age, year, captures.get(0).map_or(0, |m| m.start()))
+        } else if let Some(captures) = name.captures(date) {
+            let age = Age::from_name(&captures[1].to_lowercase());
+            (age, None, captures.get(0).map_or(0, |m| m.start()))
+        } else {
+            (None, None, 0)
+        };
+        let Some(age) = age else {
+            return Date::Unknown;
+        };
+
+        let prefix = date[..start].to_lowercase();
+        let period = Period::from_prefix(&prefix);
+        let approximate = [
+            "late", "early", "mid", "c.", "circa", "around", "about", "before", "after", "between",
+        ]
+        .iter()
+        .any(|word| prefix.contains(word));
+        let uncertain = ["?", "possibly", "probably", "likely", " or "]
+            .iter()
+            .any(|word| lowercase.contains(word));
+
+        Date::Known {
+            age,
+            year,
+            period,
+            approximate,
+            uncertain,
+        }
+    }
+
+    /// Returns `true` if the date is known.
+    pub fn is_known(&self) -> bool {
+        matches!(self, Date::Known { .. })
+    }
+
+    /// Returns the position of the date in its age: its period, a year falling in
+    /// the period of its third of the age, then its year.
+    fn position(
+        age: Age,
+        year: Option<u32>,
+        period: Option<Period>,
+    ) -> (Option<Period>, Option<u32>) {
+        match year {
+            Some(year) => (
+                Some(Period::of_year(age, year).unwrap_or(Period::Mid)),
+                Some(year),
+            ),
+            None => (period, None),
+        }
+    }
+}
+
+impl Ord for Date {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (*self, *other) {
+            (
+                Date::Known {
+                    age,
+                    year,
+                    period,
+                    approximate,
+                    uncertain,
+                },
+                Date::Known {
+                    age: other_age,
+                    year: other_year,
+                    period: other_period,
+                    approximate: other_approximate,
+                    uncertain: other_uncertain,
+                },
+            ) => age
+                .cmp(&other_age)
+                .then_with(|| {
+                    Date::position(age, year, period).cmp(&Date::position(
+                        other_age,
+                        other_year,
+                        other_period,
+                    ))
+                })
+                // Compare the other fields last, so that the order is consistent with
+                // the equality.
+                .then_with(|| {
+                    (period, approximate, uncertain).cmp(&(
+                        other_period,
+                        other_approximate,
+                        other_uncertain,
+                    ))
+                }),
+            (Date::Known { .. }, Date::Unknown) => Ordering::Less,
+            (Date::Unknown, Date::Known { .. }) => Ordering::Greater,
+            (Date::Unknown, Date::Unknown) => Ordering::Equal,
+        }
+    }
+}
+
+impl PartialOrd for Date {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Date::Known {
+                age,
+                year,
+                period,
+                approximate,
+                uncertain,
+            } => {
+                if let Some(period) = period {
+                    write!(formatter, "{} ", period)?;
+                } else if *approximate {
+                    write!(formatter, "c. ")?;
+                }
+                write!(formatter, "{}", age)?;
+                if let Some(year) = year {
+                    write!(formatter, " {}", year)?;
+                }
+                if *uncertain {
+                    write!(formatter, "?")?;
+                }
+                Ok(())
+            }
+            Date::Unknown => write!(formatter, "Unknown"),
+        }
+    }
+}
+
+/// Defines an enum of the normalized values of a field of the characters, with the value
+/// the API uses for each variant and the other spellings that are found in the API.
+macro_rules! normalized {
+    (
+        $(#[$doc:meta])*
+        $name:ident ($attribute:ident) {
+            $($variant:ident => $value:literal $(| $alias:literal)*,)*
+        }
+    ) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                #[doc = concat!("`", $value, "`.")]
+                $variant,
+            )*
+            /// Any other value, as returned by the API.
+            Other(String),
+        }
+
+        impl $name {
+            /// Parses the value returned by the API, ignoring the case. The empty values
+            /// and `NaN` mean that the value is unknown, so `None` is returned.
+            pub fn parse(value: &str) -> Option<Self> {
+                let value = value.trim();
+                if value.is_empty() || value.eq_ignore_ascii_case("nan") {
+                    return None;
+                }
+                let lowercase = value.to_lowercase();
+                $(
+                    if lowercase == $value.to_lowercase() $(|| lowercase == $alias)* {
+                        return Some($name::$variant);
+                    }
+                )*
+                Some($name::Other(value.to_string()))
+            }
+
+            /// Returns the value as written by the API.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+
+            #[doc = concat!(
+                "Returns a filter that keeps the characters whose [`CharacterAttribute::",
+                stringify!($attribute), "`] is one of the given values, in any of their spellings."
+            )]
+            ///
+            /// The API compares the values exactly, so each value is expanded into its
+            /// spelling, its other known spellings, and their lowercase and capitalized
+            /// forms. The spellings in any other case are not matched.
+            pub fn filter_eq<A: From<CharacterAttribute>>(
+                values: impl IntoIterator<Item = Self>,
+            ) -> Filter<A> {
+                Self::filter(Operator::Eq, values)
+            }
+
+            #[doc = concat!(
+                "Returns a filter that keeps the characters whose [`CharacterAttribute::",
+                stringify!($attribute), "`] is none of the given values, in any of their spellings."
+            )]
+            ///
+            #[doc = concat!("See [`", stringify!($name), "::filter_eq`].")]
+            pub fn filter_ne<A: From<CharacterAttribute>>(
+                values: impl IntoIterator<Item = Self>,
+            ) -> Filter<A> {
+                Self::filter(Operator::Ne, values)
+            }
+
+            fn filter<A: From<CharacterAttribute>>(
+                operator: Operator,
+                values: impl IntoIterator<Item = Self>,
+            ) -> Filter<A> {
+                let mut spellings: Vec<String> = Vec::new();
+                for value in values {
+                    let known: &[&str] = match &value {
+                        $($name::$variant => &[$value $(, $alias)*],)*
+                        $name::Other(value) => &[value],
+                    };
+                    for spelling in known {
+                        for spelling in [spelling.to_string(), spelling.to_lowercase(), capitalize(spelling)] {
+                            if !spellings.contains(&spelling) {
+                                spellings.push(spelling);
+                            }
+                        }
+                    }
+                }
+                Filter::Match(
+                    CharacterAttribute::$attribute.into(),
+                    operator,
+                    spellings.into_iter().map(FilterValue::String).collect(),
+                )
+            }
+        }
+
+        /// The value as written by the API. Unlike the `filter_eq` and `filter_ne`
+        /// constructors, a filter on it only matches this spelling.
+        impl From<$name> for FilterValue {
+            fn from(value: $name) -> Self {
+                FilterValue::String(value.as_str().to_string())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{}", self.as_str())
+            }
+        }
+    };
+}
+
+normalized!(
+    /// The gender of a character.
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::{
+    ///     attribute::CharacterAttribute,
+    ///     filter::{Filter, Operator},
+    ///     lore::Gender,
+    ///     request::GetUrl,
+    /// };
+    ///
+    /// let filter = Gender::filter_eq::<CharacterAttribute>([Gender::Male]);
+    /// assert_eq!(filter.get_url(), "gender=Male,male,males,Males");
+    ///
+    /// // As a value, only the spelling of the API is used.
+    /// let filter = Filter::Match(CharacterAttribute::Gender, Operator::Eq, vec![Gender::Male.into()]);
+    /// assert_eq!(filter.get_url(), "gender=Male");
+    /// ```
+    Gender(Gender) {
+        Male => "Male" | "males",
+        Female => "Female" | "females",
+    }
+);
+
+normalized!(
+    /// The realm of a character.
+    Realm(Realm) {
+        Arnor => "Arnor",
+        Doriath => "Doriath",
+        Erebor => "Erebor",
+        Gondolin => "Gondolin",
+        Gondor => "Gondor",
+        Isengard => "Isengard",
+        Lothlorien => "Lothlórien" | "lothlorien" | "lorien" | "lórien",
+        Mirkwood => "Mirkwood" | "woodland realm",
+        Mordor => "Mordor",
+        Numenor => "Númenor" | "numenor",
+        ReunitedKingdom => "Reunited Kingdom",
+        Rivendell => "Rivendell",
+        Rohan => "Rohan",
+        Shire => "The Shire" | "shire",
+        Valinor => "Valinor",
+    }
+);
+
+normalized!(
+    /// The hair of a character.
+    Hair(Hair) {
+        Black => "Black",
+        Blond => "Blond" | "blonde",
+        Brown => "Brown",
+        Dark => "Dark",
+        Golden => "Golden",
+        Grey => "Grey" | "gray",
+        Red => "Red",
+        Silver => "Silver",
+        White => "White",
+    }
+);
+
+/// Returns the value with its first letter in uppercase.
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl Character {
+    /// Returns the parsed height of the character, or `None` if it is unknown.
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::Character;
+    ///
+    /// let frodo: Character = serde_json::from_str(
+    ///     r#"{"_id": "5cd99d4bde30eff6ebccfc15", "name": "Frodo Baggins", "height": "1.06m (3'6\")"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(frodo.parsed_height().unwrap().centimeters().round(), 106.0);
+    /// ```
+    pub fn parsed_height(&self) -> Option<Height> {
+        self.height.as_deref().and_then(Height::parse)
+    }
+
+    /// Returns the parsed date of birth of the character. Sorting the characters by it
+    /// sorts them chronologically: `characters.sort_by_key(Character::birth_date)`.
+    pub fn birth_date(&self) -> Date {
+        self.birth.as_deref().map_or(Date::Unknown, Date::parse)
+    }
+
+    /// Returns the parsed date of death of the character.
+    pub fn death_date(&self) -> Date {
+        self.death.as_deref().map_or(Date::Unknown, Date::parse)
+    }
+
+    /// Returns the normalized gender of the character, or `None` if it is unknown.
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::{Character, lore::Gender};
+    ///
+    /// let character: Character = serde_json::from_str(
+    ///     r#"{"_id": "5cd99d4bde30eff6ebccfc15", "name": "Frodo Baggins", "gender": "male"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(character.parsed_gender(), Some(Gender::Male));
+    /// ```
+    pub fn parsed_gender(&self) -> Option<Gender> {
+        self.gender.as_deref().and_then(Gender::parse)
+    }
+
+    /// Returns the normalized realm of the character, or `None` if it is unknown.
+    pub fn parsed_realm(&self) -> Option<Realm> {
+        self.realm.as_deref().and_then(Realm::parse)
+    }
+
+    /// Returns the normalized hair of the character, or `None` if it is unknown.
+    pub fn parsed_hair(&self) -> Option<Hair> {
+        self.hair.as_deref().and_then(Hair::parse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_height() {
+        let centimeters = |height: &str| Height::parse(height).map(|h| h.centimeters().round());
+        assert_eq!(centimeters("198cm (6'6\")"), Some(198.0));
+        assert_eq!(centimeters("1.06m (3'6\")"), Some(106.0));
+        assert_eq!(centimeters("6'6\""), Some(198.0));
+        assert_eq!(centimeters("5'"), Some(152.0));
+        assert_eq!(centimeters("Tall"), None);
+        assert_eq!(centimeters("NaN"), None);
+        assert_eq!(centimeters(""), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let known = |age, year, approximate, uncertain| Date::Known {
+            age,
+            year,
+            period: None,
+            approximate,
+            uncertain,
+        };
+        assert_eq!(
+            Date::parse("TA 241"),
+            known(Age::Third, Some(241), false, false)
+        );
+        assert_eq!(
+            Date::parse("22 September ,TA 2968"),
+            known(Age::Third, Some(2968), false, false)
+        );
+        assert_eq!(
+            Date::parse("FO 120"),
+            known(Age::Fourth, Some(120), false, false)
+        );
+        assert_eq!(
+            Date::parse("YT 1362"),
+            known(Age::YearsOfTheTrees, Some(1362), false, false)
+        );
+        assert_eq!(
+            Date::parse("Mid ,First Age"),
+            Date::Known {
+                age: Age::First,
+                year: None,
+                period: Some(Period::Mid),
+                approximate: true,
+                uncertain: false,
+            }
+        );
+        assert_eq!(
+            Date::parse("c. SA 1600"),
+            known(Age::Second, Some(1600), true, false)
+        );
+        assert_eq!(
+            Date::parse("TA 3019 (possibly)"),
+            known(Age::Third, Some(3019), false, true)
+        );
+        for unknown in [
+            "",
+            "NaN",
+            "Unknown",
+            "Still alive",
+            "Before the the Shaping of Arda",
+            "Unknown (Last sighting ,September 29 ,TA 3021,) (SR 1421,)",
+        ] {
+            assert_eq!(Date::parse(unknown), Date::Unknown, "{}", unknown);
+        }
+    }
+
+    #[test]
+    fn test_chronological_order() {
+        let mut dates: Vec<_> = [
+            "Unknown",
+            "FO 1",
+            "TA 2931",
+            "Late ,Third Age",
+            "SA 3441",
+            "TA 241",
+            "Early ,Third Age",
+            "Mid ,Third Age",
+            "TA 1500",
+            "Third Age",
+            "FO 120",
+        ]
+        .into_iter()
+        .map(Date::parse)
+        .collect();
+        dates.sort();
+        let dates: Vec<_> = dates.iter().map(Date::to_string).collect();
+        assert_eq!(
+            dates,
+            vec![
+                "SA 3441", "TA", "Early TA", "TA 241", "Mid TA", "TA 1500", "Late TA", "TA 2931",
+                "FO 1", "FO 120", "Unknown"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_order_of_large_years() {
+        let large = Date::parse("TA 2000000000");
+        assert!(large > Date::parse("TA 1"));
+        assert!(large > Date::parse("Late ,Third Age"));
+        assert!(large < Date::parse("FO 1"));
+        assert!(Date::parse(&format!("TA {}", u32::MAX)) > large);
+    }
+
+    #[test]
+    fn test_parse_normalized() {
+        assert_eq!(Gender::parse("Male"), Some(Gender::Male));
+        assert_eq!(Gender::parse(" male "), Some(Gender::Male));
+        assert_eq!(Gender::parse("Females"), Some(Gender::Female));
+        assert_eq!(Gender::parse(""), None);
+        assert_eq!(Gender::parse("NaN"), None);
+        assert_eq!(
+            Gender::parse("Plural"),
+            Some(Gender::Other("Plural".to_string()))
+        );
+
+        assert_eq!(Realm::parse("Lothlorien"), Some(Realm::Lothlorien));
+        assert_eq!(Realm::parse("Lothlorien").unwrap().as_str(), "Lothlórien");
+        assert_eq!(Realm::parse("Shire"), Some(Realm::Shire));
+        assert_eq!(Hair::parse("Gray"), Some(Hair::Grey));
+        assert_eq!(
+            Hair::parse("Dark (formerly)").unwrap().to_string(),
+            "Dark (formerly)"
+        );
+    }
+
+    #[test]
+    fn test_normalized_filter() {
+        use crate::{
+            attribute::{Attribute, CharacterAttribute},
+            request::GetUrl,
+        };
+
+        let filter =
+            Realm::filter_eq::<CharacterAttribute>([Realm::Gondor, Realm::ReunitedKingdom]);
+        assert_eq!(
+            filter.get_url(),
+            "realm=Gondor,gondor,Reunited%20Kingdom,reunited%20kingdom"
+        );
+
+        let filter = Hair::filter_ne::<Attribute>([Hair::Grey]);
+        assert_eq!(filter.get_url(), "hair!=Grey,grey,gray,Gray");
+
+        let filter = Gender::filter_eq::<CharacterAttribute>([Gender::Other("Plural".to_string())]);
+        assert_eq!(filter.get_url(), "gender=Plural,plural");
+
+        let filter = Filter::Match(
+            CharacterAttribute::Realm,
+            Operator::Eq,
+            vec![Realm::Gondor.into(), Realm::ReunitedKingdom.into()],
+        );
+        assert_eq!(filter.get_url(), "realm=Gondor,Reunited%20Kingdom");
+    }
+}
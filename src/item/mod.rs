@@ -2,13 +2,24 @@
 //! It also holds the [`attribute::Attribute`] enum and its derivatives, that contain the attributes
 //! that represent the fields of the items ( they are used for filtering and sorting ).
 
-use self::object::{Book, Chapter, Character, Movie, Quote};
+use self::{
+    attribute::{
+        Attribute, BookAttribute, ChapterAttribute, CharacterAttribute, MovieAttribute,
+        QuoteAttribute,
+    },
+    id::{BookId, ChapterId, CharacterId, Id, MovieId, QuoteId},
+    object::{Book, Chapter, Character, Movie, Quote},
+};
 
 pub mod attribute;
+pub mod hydrated;
+pub mod id;
+pub mod lore;
 pub mod object;
 
 /// The different types of items that can be retrieved from the API.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ItemType {
     Book,
     Movie,
@@ -17,6 +28,35 @@ pub enum ItemType {
     Chapter,
 }
 
+impl std::fmt::Display for ItemType {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            ItemType::Book => "book",
+            ItemType::Movie => "movie",
+            ItemType::Quote => "quote",
+            ItemType::Character => "character",
+            ItemType::Chapter => "chapter",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+impl std::str::FromStr for ItemType {
+    type Err = crate::Error;
+
+    /// Parses the item type from its name in the urls, like `book` or `character`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "book" => Ok(ItemType::Book),
+            "movie" => Ok(ItemType::Movie),
+            "quote" => Ok(ItemType::Quote),
+            "character" => Ok(ItemType::Character),
+            "chapter" => Ok(ItemType::Chapter),
+            _ => Err(crate::Error::UnknownItemType(value.to_string())),
+        }
+    }
+}
+
 impl From<&str> for ItemType {
     fn from(value: &str) -> Self {
         match value {
@@ -30,6 +70,173 @@ impl From<&str> for ItemType {
     }
 }
 
+/// This trait is implemented by the objects that can be retrieved from the API.
+/// It links each object to its [`ItemType`] and to the enum of its attributes,
+/// which allows to build typed requests ( see [`crate::RequestBuilder::typed`] ).
+pub trait TypedItem:
+    serde::Serialize + serde::de::DeserializeOwned + Clone + Into<Item> + Send + 'static
+{
+    /// The attributes of the object, used to sort and filter the requests.
+    type Attribute: Into<Attribute> + Copy;
+
+    /// The id of the object.
+    type Id: Id;
+
+    /// Returns the item type of the object.
+    fn item_type() -> ItemType;
+}
+
+impl TypedItem for Book {
+    type Attribute = BookAttribute;
+    type Id = BookId;
+
+    fn item_type() -> ItemType {
+        ItemType::Book
+    }
+}
+
+impl TypedItem for Movie {
+    type Attribute = MovieAttribute;
+    type Id = MovieId;
+
+    fn item_type() -> ItemType {
+        ItemType::Movie
+    }
+}
+
+impl TypedItem for Quote {
+    type Attribute = QuoteAttribute;
+    type Id = QuoteId;
+
+    fn item_type() -> ItemType {
+        ItemType::Quote
+    }
+}
+
+impl TypedItem for Character {
+    type Attribute = CharacterAttribute;
+    type Id = CharacterId;
+
+    fn item_type() -> ItemType {
+        ItemType::Character
+    }
+}
+
+impl TypedItem for Chapter {
+    type Attribute = ChapterAttribute;
+    type Id = ChapterId;
+
+    fn item_type() -> ItemType {
+        ItemType::Chapter
+    }
+}
+
+/// This trait is implemented by all the objects of the API, which all have an id.
+/// It allows generic code to handle any object, like [`crate::Client::get_by_id`].
+///
+/// # Example
+/// ```
+/// use lotr_api::{ApiObject, Book, ItemType, TypedItem};
+///
+/// fn describe<T: ApiObject>(object: &T) -> String {
+///     format!("{} {}: {}", T::item_type(), object.id(), object.display_name().unwrap_or("?"))
+/// }
+///
+/// let book: Book = serde_json::from_str(
+///     r#"{"_id": "5cf58077b53e011a64671583", "name": "The Two Towers"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(describe(&book), "book 5cf58077b53e011a64671583: The Two Towers");
+/// ```
+pub trait ApiObject: TypedItem {
+    /// Returns the id of the object.
+    fn id(&self) -> &Self::Id;
+
+    /// Returns the name of the object, or the dialog of a quote.
+    fn display_name(&self) -> Option<&str>;
+}
+
+/// This trait is implemented by the objects that have a name, which are all of them but the quotes.
+pub trait Named: ApiObject {
+    /// Returns the name of the object.
+    fn name(&self) -> &str;
+}
+
+impl ApiObject for Book {
+    fn id(&self) -> &BookId {
+        &self._id
+    }
+
+    fn display_name(&self) -> Option<&str> {
+        Some(self.name())
+    }
+}
+
+impl Named for Book {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ApiObject for Movie {
+    fn id(&self) -> &MovieId {
+        &self._id
+    }
+
+    fn display_name(&self) -> Option<&str> {
+        Some(self.name())
+    }
+}
+
+impl Named for Movie {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ApiObject for Quote {
+    fn id(&self) -> &QuoteId {
+        // The API returns the id of a quote twice, as `_id` and `id`.
+        &self.id
+    }
+
+    fn display_name(&self) -> Option<&str> {
+        self.dialog.as_deref()
+    }
+}
+
+impl ApiObject for Character {
+    fn id(&self) -> &CharacterId {
+        &self._id
+    }
+
+    fn display_name(&self) -> Option<&str> {
+        Some(self.name())
+    }
+}
+
+impl Named for Character {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ApiObject for Chapter {
+    fn id(&self) -> &ChapterId {
+        &self._id
+    }
+
+    fn display_name(&self) -> Option<&str> {
+        Some(self.name())
+    }
+}
+
+impl Named for Chapter {
+    fn name(&self) -> &str {
+        &self.chapter_name
+    }
+}
+
 /// The different items that can be retrieved from the API.
 /// They are all wrapped in this enum, so that they can be used in the same vector.
 #[derive(Debug, Clone, PartialEq)]
@@ -41,6 +248,53 @@ pub enum Item {
     Chapter(Chapter),
 }
 
+impl Item {
+    /// Returns the item type of the item.
+    pub fn item_type(&self) -> ItemType {
+        match self {
+            Item::Book(_) => ItemType::Book,
+            Item::Movie(_) => ItemType::Movie,
+            Item::Quote(_) => ItemType::Quote,
+            Item::Character(_) => ItemType::Character,
+            Item::Chapter(_) => ItemType::Chapter,
+        }
+    }
+
+    /// Returns the id of the item.
+    pub fn id(&self) -> &str {
+        match self {
+            Item::Book(book) => book.id().as_str(),
+            Item::Movie(movie) => movie.id().as_str(),
+            Item::Quote(quote) => quote.id().as_str(),
+            Item::Character(character) => character.id().as_str(),
+            Item::Chapter(chapter) => chapter.id().as_str(),
+        }
+    }
+
+    /// Returns the name of the item, or the dialog of a quote. See [`ApiObject::display_name`].
+    pub fn display_name(&self) -> Option<&str> {
+        match self {
+            Item::Book(book) => book.display_name(),
+            Item::Movie(movie) => movie.display_name(),
+            Item::Quote(quote) => quote.display_name(),
+            Item::Character(character) => character.display_name(),
+            Item::Chapter(chapter) => chapter.display_name(),
+        }
+    }
+
+    /// Returns the item as the JSON returned by the API.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        match self {
+            Item::Book(book) => serde_json::to_value(book),
+            Item::Movie(movie) => serde_json::to_value(movie),
+            Item::Quote(quote) => serde_json::to_value(quote),
+            Item::Character(character) => serde_json::to_value(character),
+            Item::Chapter(chapter) => serde_json::to_value(chapter),
+        }
+        .expect("the items serialize into JSON objects")
+    }
+}
+
 impl From<Book> for Item {
     fn from(book: Book) -> Self {
         Item::Book(book)
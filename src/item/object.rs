@@ -1,47 +1,17 @@
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::Item;
+use super::id::{BookId, ChapterId, CharacterId, MovieId, QuoteId};
 
 /// Struct for deserializing the Json response
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
-    docs: Vec<T>,
-    total: u32,
-    limit: u32,
-    offset: u32,
-    page: Option<u32>,
-    pages: Option<u32>,
-}
-
-impl From<Response<Book>> for Vec<Item> {
-    fn from(response: Response<Book>) -> Self {
-        response.docs.into_iter().map(Item::from).collect()
-    }
-}
-
-impl From<Response<Movie>> for Vec<Item> {
-    fn from(response: Response<Movie>) -> Self {
-        response.docs.into_iter().map(Item::from).collect()
-    }
-}
-
-impl From<Response<Quote>> for Vec<Item> {
-    fn from(response: Response<Quote>) -> Self {
-        response.docs.into_iter().map(Item::from).collect()
-    }
-}
-
-impl From<Response<Character>> for Vec<Item> {
-    fn from(response: Response<Character>) -> Self {
-        response.docs.into_iter().map(Item::from).collect()
-    }
-}
-
-impl From<Response<Chapter>> for Vec<Item> {
-    fn from(response: Response<Chapter>) -> Self {
-        response.docs.into_iter().map(Item::from).collect()
-    }
+    pub(crate) docs: Vec<T>,
+    pub(crate) total: u32,
+    pub(crate) limit: u32,
+    pub(crate) offset: u32,
+    pub(crate) page: Option<u32>,
+    pub(crate) pages: Option<u32>,
 }
 
 impl<T> Response<T> {
@@ -52,13 +22,17 @@ impl<T> Response<T> {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Book {
-    pub _id: String,
+    pub _id: BookId,
     pub name: String,
+
+    /// The fields returned by the API that this struct doesn't know about yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Movie {
-    pub _id: String,
+    pub _id: MovieId,
     pub name: String,
 
     #[serde(rename = "runtimeInMinutes")]
@@ -78,23 +52,32 @@ pub struct Movie {
 
     #[serde(rename = "rottenTomatoesScore")]
     pub rotten_tomates_score: f32,
+
+    /// The fields returned by the API that this struct doesn't know about yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Quote {
-    pub _id: String,
+    pub _id: QuoteId,
     // This should be a String but until https://github.com/gitfrosh/lotr-api/issues/151 gets
     // resolved it must be an optional value
     pub dialog: Option<String>,
-    pub movie: String,
-    pub character: String,
-    pub id: String,
+    pub movie: MovieId,
+    pub character: CharacterId,
+    pub id: QuoteId,
+
+    /// The fields returned by the API that this struct doesn't know about yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Character {
-    pub _id: String,
+    pub _id: CharacterId,
     pub height: Option<String>,
+    pub race: Option<String>,
     pub gender: Option<String>,
     pub birth: Option<String>,
     pub spouse: Option<String>,
@@ -105,20 +88,30 @@ pub struct Character {
 
     #[serde(rename = "wikiUrl")]
     pub wiki_url: Option<String>,
+
+    /// The fields returned by the API that this struct doesn't know about yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Chapter {
-    pub _id: String,
+    pub _id: ChapterId,
 
     #[serde(rename = "chapterName")]
     pub chapter_name: String,
 
-    pub book: String,
+    pub book: BookId,
+
+    /// The fields returned by the API that this struct doesn't know about yet.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 #[cfg(test)]
 mod test {
+    use crate::Id;
+
     use super::*;
 
     #[test]
@@ -152,4 +145,38 @@ mod test {
             serde_json::from_str::<Movie>(json).unwrap();
         }
     }
+
+    /// Deserializes a response written in `tests/fixtures`, in the format of the API.
+    fn docs<T: serde::de::DeserializeOwned>(response: &str) -> Vec<T> {
+        serde_json::from_str::<Response<T>>(response).unwrap().docs
+    }
+
+    #[test]
+    fn test_deserialize_fixtures() {
+        let books: Vec<Book> = docs(include_str!("../../tests/fixtures/book.json"));
+        assert_eq!(books[1].name, "The Two Towers");
+
+        let movies: Vec<Movie> = docs(include_str!("../../tests/fixtures/movie.json"));
+        assert!(movies.iter().any(|movie| movie.academy_award_wins == 11));
+
+        let quotes: Vec<Quote> = docs(include_str!("../../tests/fixtures/quote.json"));
+        assert_eq!(quotes[0].dialog.as_deref(), Some("Deagol!"));
+        assert_eq!(quotes[2].dialog, None);
+
+        let characters: Vec<Character> = docs(include_str!("../../tests/fixtures/character.json"));
+        assert_eq!(characters[0].race.as_deref(), Some("Human"));
+        assert_eq!(characters[1].race.as_deref(), Some("Hobbit"));
+        assert_eq!(characters[2].height, None);
+
+        let chapters: Vec<Chapter> = docs(include_str!("../../tests/fixtures/chapter.json"));
+        assert_eq!(chapters[2].book.as_str(), "5cf58077b53e011a64671583");
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let json = r#"{"_id":"5cf5805fb53e011a64671582","name":"The Fellowship Of The Ring","author":"J. R. R. Tolkien"}"#;
+        let book: Book = serde_json::from_str(json).unwrap();
+        assert_eq!(book.extra["author"], "J. R. R. Tolkien");
+        assert_eq!(serde_json::to_string(&book).unwrap(), json);
+    }
 }
@@ -29,7 +29,7 @@
 //!     .filter(Filter::Match(
 //!         Attribute::Book(BookAttribute::Name),
 //!         Operator::Eq,
-//!         vec!["The Fellowship of the Ring".to_string()])
+//!         vec!["The Fellowship of the Ring".into()])
 //!     )
 //!     .sort(Sort::new(SortOrder::Ascending, Attribute::Book(BookAttribute::Name)))
 //!     .build()
@@ -42,7 +42,20 @@
 //! # Features
 //!
 //! - [`Client`] functions to get all items of a type .
+//! - [`ClientBuilder`] to configure the client ( base url, timeouts, headers... ).
+//! - [`transport::Transport`] to replace the HTTP layer, for example with a [`transport::FixtureTransport`] in tests.
 //! - [`RequestBuilder`] to build a request with filters, pagination and sorting, which allows the user full control over the request without having to deal with the url.
+//!   With [`RequestBuilder::typed`] the request is checked against the attributes of an item at compile time and returns that item.
+//! - Filters can be combined with [`filter::Filter::And`], [`filter::Filter::Or`] and [`filter::Filter::Not`], and requests sorted by several attributes.
+//!   What the API can't express is evaluated by the client, see [`request::plan::Plan`].
+//! - [`Request::apply`] evaluates a request on items that are already in memory, with the same semantics as the API.
+//! - [`ApiObject`] and [`Named`] give access to the id and the name of any object, and [`Client::get_by_id`] gets any object by its id.
+//! - Each item type has its own id type, like [`BookId`] or [`CharacterId`], so that an id can't be used for the wrong item type.
+//! - The objects keep the fields they don't know about in `extra`, and [`request::decode::DecodeMode::Lenient`] skips the documents that can't be deserialized instead of failing.
+//! - The height, birth and death of the characters are parsed into a [`lore::Height`] and [`lore::Date`]s, see [`Character::birth_date`],
+//!   and their gender, realm and hair are normalized into a [`lore::Gender`], a [`lore::Realm`] and a [`lore::Hair`], which can filter the characters.
+//! - [`Client::hydrate`] fetches the movies and characters of quotes, and the books of chapters.
+//! - [`Request`] can be parsed back from its url, and serialized with serde to be saved ( see [`request::FORMAT_VERSION`] ).
 //!
 //!
 
@@ -50,15 +63,24 @@ pub mod client;
 pub mod error;
 pub mod item;
 pub mod request;
+pub mod transport;
 
-pub use client::Client;
+pub use client::{Client, ClientBuilder};
 pub use error::Error;
 pub use item::attribute;
+pub use item::hydrated::{Hydrate, HydratedChapter, HydratedQuote};
+pub use item::id::{BookId, ChapterId, CharacterId, Id, MovieId, QuoteId};
+pub use item::lore;
 pub use item::object::*;
-pub use item::Item;
 pub use item::ItemType;
+pub use item::TypedItem;
+pub use item::{ApiObject, Item, Named};
 pub use request::filter;
+pub use request::page::Page;
 pub use request::pagination::Pagination;
+pub use request::rate_limit::RateLimit;
+pub use request::retry::RetryPolicy;
 pub use request::sort;
 pub use request::Request;
 pub use request::RequestBuilder;
+pub use request::TypedRequest;
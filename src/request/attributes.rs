@@ -6,7 +6,102 @@ use crate::{
     ItemType,
 };
 
-use super::GetUrl;
+use super::{filter::ValueKind, GetUrl};
+
+const BOOK_ATTRIBUTES: [BookAttribute; 2] = [BookAttribute::Id, BookAttribute::Name];
+
+const MOVIE_ATTRIBUTES: [MovieAttribute; 8] = [
+    MovieAttribute::Id,
+    MovieAttribute::Name,
+    MovieAttribute::RuntimeInMinutes,
+    MovieAttribute::BudgetInMillions,
+    MovieAttribute::BoxOfficeRevenueInMillions,
+    MovieAttribute::AcademyAwardNominations,
+    MovieAttribute::AcademyAwardWins,
+    MovieAttribute::RottenTomatoesScore,
+];
+
+const QUOTE_ATTRIBUTES: [QuoteAttribute; 4] = [
+    QuoteAttribute::Id,
+    QuoteAttribute::Dialog,
+    QuoteAttribute::Movie,
+    QuoteAttribute::Character,
+];
+
+const CHARACTER_ATTRIBUTES: [CharacterAttribute; 11] = [
+    CharacterAttribute::Id,
+    CharacterAttribute::Height,
+    CharacterAttribute::Race,
+    CharacterAttribute::Gender,
+    CharacterAttribute::Birth,
+    CharacterAttribute::Spouse,
+    CharacterAttribute::Death,
+    CharacterAttribute::Realm,
+    CharacterAttribute::Hair,
+    CharacterAttribute::Name,
+    CharacterAttribute::WikiUrl,
+];
+
+const CHAPTER_ATTRIBUTES: [ChapterAttribute; 3] = [
+    ChapterAttribute::Id,
+    ChapterAttribute::ChapterName,
+    ChapterAttribute::Book,
+];
+
+/// Returns the attribute whose url is the given name.
+fn find<A: GetUrl + Copy>(attributes: &[A], name: &str) -> Option<A> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.get_url() == name)
+        .copied()
+}
+
+impl Attribute {
+    /// Returns the kind of the values of the attribute, which are the values that it can be
+    /// compared to in a [`crate::filter::Filter::Match`].
+    pub fn value_kind(&self) -> ValueKind {
+        match self {
+            Attribute::Book(BookAttribute::Id)
+            | Attribute::Movie(MovieAttribute::Id)
+            | Attribute::Quote(
+                QuoteAttribute::Id | QuoteAttribute::Movie | QuoteAttribute::Character,
+            )
+            | Attribute::Character(CharacterAttribute::Id)
+            | Attribute::Chapter(ChapterAttribute::Id | ChapterAttribute::Book) => ValueKind::Id,
+            Attribute::Movie(
+                MovieAttribute::RuntimeInMinutes
+                | MovieAttribute::BudgetInMillions
+                | MovieAttribute::BoxOfficeRevenueInMillions
+                | MovieAttribute::AcademyAwardNominations
+                | MovieAttribute::AcademyAwardWins
+                | MovieAttribute::RottenTomatoesScore,
+            ) => ValueKind::Number,
+            _ => ValueKind::String,
+        }
+    }
+
+    /// Returns the `_id` attribute of the given item type.
+    pub(crate) fn id(item_type: &ItemType) -> Self {
+        match item_type {
+            ItemType::Book => Self::Book(BookAttribute::Id),
+            ItemType::Movie => Self::Movie(MovieAttribute::Id),
+            ItemType::Quote => Self::Quote(QuoteAttribute::Id),
+            ItemType::Character => Self::Character(CharacterAttribute::Id),
+            ItemType::Chapter => Self::Chapter(ChapterAttribute::Id),
+        }
+    }
+
+    /// Returns the attribute of the given item type that is written as `name` in the urls.
+    pub(crate) fn from_url(item_type: &ItemType, name: &str) -> Option<Self> {
+        match item_type {
+            ItemType::Book => find(&BOOK_ATTRIBUTES, name).map(Self::Book),
+            ItemType::Movie => find(&MOVIE_ATTRIBUTES, name).map(Self::Movie),
+            ItemType::Quote => find(&QUOTE_ATTRIBUTES, name).map(Self::Quote),
+            ItemType::Character => find(&CHARACTER_ATTRIBUTES, name).map(Self::Character),
+            ItemType::Chapter => find(&CHAPTER_ATTRIBUTES, name).map(Self::Chapter),
+        }
+    }
+}
 
 impl GetUrl for ItemType {
     fn get_url(&self) -> String {
@@ -76,6 +171,7 @@ impl GetUrl for CharacterAttribute {
         match self {
             Self::Id => "_id",
             Self::Height => "height",
+            Self::Race => "race",
             Self::Gender => "gender",
             Self::Birth => "birth",
             Self::Spouse => "spouse",
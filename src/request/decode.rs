@@ -0,0 +1,117 @@
+//! Decoding of the documents returned by the API.
+//!
+//! By default a document that can't be deserialized makes the whole request fail.
+//! With [`DecodeMode::Lenient`] the document is skipped instead, and reported in
+//! [`super::page::Page::skipped`].
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::Error;
+
+/// How the client handles the documents that can't be deserialized, for example
+/// because the API changed the type of one of their fields.
+///
+/// The mode only applies to the methods that return a [`super::page::Page`], which
+/// holds the skipped documents: [`crate::Client::get_page`] and
+/// [`crate::Client::get_page_typed`]. The other methods, like [`crate::Client::get`],
+/// [`crate::Client::stream`] or [`crate::Client::hydrate`], always decode strictly,
+/// so that no item goes missing silently.
+///
+/// # Example
+/// ```
+/// use lotr_api::{ClientBuilder, request::decode::DecodeMode};
+///
+/// let client = ClientBuilder::new("your_token".to_string())
+///     .decode_mode(DecodeMode::Lenient)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeMode {
+    /// A document that can't be deserialized fails the request with an [`Error::Decode`].
+    #[default]
+    Strict,
+    /// The documents that can't be deserialized are skipped and reported.
+    Lenient,
+}
+
+/// A document of a response that was skipped because it couldn't be deserialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedDocument {
+    index: usize,
+    document: Value,
+    error: String,
+}
+
+impl SkippedDocument {
+    /// Returns the position of the document in the page.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the document as returned by the API.
+    pub fn document(&self) -> &Value {
+        &self.document
+    }
+
+    /// Returns the reason why the document couldn't be deserialized.
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+/// Deserializes the documents of the response of the given url, returning the
+/// documents that were skipped in lenient mode.
+pub(crate) fn decode<T: DeserializeOwned>(
+    documents: Vec<Value>,
+    mode: DecodeMode,
+    url: &str,
+) -> Result<(Vec<T>, Vec<SkippedDocument>), Error> {
+    let mut items = Vec::with_capacity(documents.len());
+    let mut skipped = Vec::new();
+    for (index, document) in documents.into_iter().enumerate() {
+        // The document is only kept when it can't be deserialized.
+        match T::deserialize(&document) {
+            Ok(item) => items.push(item),
+            Err(error) if mode == DecodeMode::Lenient => skipped.push(SkippedDocument {
+                index,
+                document,
+                error: error.to_string(),
+            }),
+            Err(error) => return Err(Error::decode(url, &document.to_string(), error)),
+        }
+    }
+    Ok((items, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::Book;
+
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let documents = vec![
+            json!({ "_id": "5cf5805fb53e011a64671582", "name": "The Fellowship Of The Ring" }),
+            json!({ "_id": "5cf58077b53e011a64671583", "name": 2 }),
+            json!({ "_id": "5cf58080b53e011a64671584", "name": "The Return Of The King" }),
+        ];
+
+        let (books, skipped) =
+            decode::<Book>(documents.clone(), DecodeMode::Lenient, "book").unwrap();
+        assert_eq!(books.len(), 2);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].index(), 1);
+        assert_eq!(skipped[0].document(), &documents[1]);
+        assert!(!skipped[0].error().is_empty());
+
+        assert!(matches!(
+            decode::<Book>(documents, DecodeMode::Strict, "book"),
+            Err(Error::Decode { ref url, .. }) if url == "book"
+        ));
+    }
+}
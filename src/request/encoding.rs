@@ -0,0 +1,85 @@
+//! Encoding of the values that are inserted in the urls of the requests.
+//!
+//! Every value that comes from the user ( ids, filter values, regex patterns ) is
+//! percent-encoded the same way as javascript's `encodeURIComponent`, so characters like
+//! `&`, `#`, `/` or `=` can't change the meaning of the url. Commas are encoded too,
+//! because the API uses them to separate the values of a filter.
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// The characters that are encoded: everything but `A-Z a-z 0-9 - _ . ! ~ * ' ( )`.
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'!')
+    .remove(b'~')
+    .remove(b'*')
+    .remove(b'\'')
+    .remove(b'(')
+    .remove(b')');
+
+/// Encodes a single value so it can be inserted in a url.
+pub(crate) fn encode(value: &str) -> String {
+    utf8_percent_encode(value, COMPONENT).to_string()
+}
+
+/// Encodes each value and joins them with commas.
+pub(crate) fn encode_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| encode(value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use percent_encoding::percent_decode_str;
+
+    use super::*;
+
+    fn decode(value: &str) -> String {
+        percent_decode_str(value).decode_utf8().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_encode() {
+        let tests = vec![
+            ("Deagol!", "Deagol!"),
+            (
+                "The Fellowship of the Ring",
+                "The%20Fellowship%20of%20the%20Ring",
+            ),
+            ("Salt & Pepper", "Salt%20%26%20Pepper"),
+            ("Bree, the village", "Bree%2C%20the%20village"),
+            ("#1", "%231"),
+            ("and/or", "and%2For"),
+            ("a=b", "a%3Db"),
+            ("1+1", "1%2B1"),
+            ("100%", "100%25"),
+            ("a/b?c&d", "a%2Fb%3Fc%26d"),
+            ("Éowyn", "%C3%89owyn"),
+            ("Nazgûl", "Nazg%C3%BBl"),
+        ];
+
+        for (value, expected) in tests {
+            assert_eq!(encode(value), expected);
+            assert_eq!(decode(&encode(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_encode_list() {
+        let values = vec![
+            "Gondor".to_string(),
+            "Bree, the village".to_string(),
+            "Éowyn".to_string(),
+        ];
+        let encoded = encode_list(&values);
+        assert_eq!(encoded, "Gondor,Bree%2C%20the%20village,%C3%89owyn");
+
+        let decoded: Vec<String> = encoded.split(',').map(decode).collect();
+        assert_eq!(decoded, values);
+    }
+}
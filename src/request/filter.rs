@@ -1,6 +1,12 @@
-use crate::{attribute::Attribute, ItemType};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use super::GetUrl;
+use std::fmt;
+
+use crate::{attribute::Attribute, item::id, Error, ItemType};
+
+use super::{encoding, GetUrl};
 
 /// A filter that can be used to filter the results of a request.
 ///
@@ -17,23 +23,113 @@ use super::GetUrl;
 /// let filter = Filter::Match(
 ///     Attribute::Book(BookAttribute::Name),
 ///     Operator::Eq,
-///     vec!["The Fellowship of the Ring".to_string()]);
+///     vec!["The Fellowship of the Ring".into()]);
+///
+/// assert_eq!(filter.get_url(), "name=The%20Fellowship%20of%20the%20Ring");
+/// ```
+///
+/// The values of a [`Filter::Match`] are [`FilterValue`]s, and they must be of the kind
+/// of the attribute ( see [`Attribute::value_kind`] ). Only numbers can be compared
+/// with [`Operator::Gt`], [`Operator::Lt`], [`Operator::Gte`] and [`Operator::Lte`].
+///
+/// ```
+/// use lotr_api::{
+///     attribute::MovieAttribute,
+///     request::GetUrl,
+///     filter::{Filter, Operator}};
+///
+/// let filter = Filter::Match(MovieAttribute::BudgetInMillions, Operator::Gt, vec![100.into()]);
+/// assert_eq!(filter.get_url(), "budgetInMillions>100");
+/// ```
+///
+/// The attribute can also be the attribute of a specific item, which is what
+/// typed requests expect ( see [`crate::RequestBuilder::typed`] ).
+///
+/// ```
+/// use lotr_api::{
+///     attribute::CharacterAttribute,
+///     request::GetUrl,
+///     filter::Filter};
+///
+/// let filter = Filter::Exists(CharacterAttribute::Spouse, false);
+/// assert_eq!(filter.get_url(), "!spouse");
+/// ```
+///
+/// A regular expression can be used to match the values, with [`Operator::Eq`]
+/// to keep the items that match it or [`Operator::Ne`] to keep the ones that don't.
+///
+/// ```
+/// use lotr_api::{
+///     attribute::CharacterAttribute,
+///     request::GetUrl,
+///     filter::{Filter, Operator}};
+///
+/// let filter = Filter::Regex(
+///     CharacterAttribute::Name,
+///     Operator::Eq,
+///     "foot".to_string(),
+///     "i".to_string());
+/// assert_eq!(filter.get_url(), "name=/foot/i");
+/// ```
+///
+/// Filters can be combined with [`Filter::And`], [`Filter::Or`] and [`Filter::Not`].
+/// The url only holds the combinations that the API can express, the others are
+/// evaluated by the [`crate::Client`] on the items it fetches ( see [`crate::request::plan::Plan`] ).
+///
+/// ```
+/// use lotr_api::{
+///     attribute::CharacterAttribute,
+///     request::GetUrl,
+///     filter::{Filter, Operator}};
 ///
-/// assert_eq!(filter.get_url(), "name=The Fellowship of the Ring");
+/// let filter = Filter::Not(Box::new(Filter::Or(vec![
+///     Filter::Match(CharacterAttribute::Realm, Operator::Eq, vec!["Gondor".into()]),
+///     Filter::Match(CharacterAttribute::Realm, Operator::Eq, vec!["Rohan".into()]),
+/// ])));
+/// assert_eq!(filter.get_url(), "realm!=Gondor,Rohan");
+///
+/// let filter = Filter::Or(vec![
+///     Filter::Match(CharacterAttribute::Realm, Operator::Eq, vec!["Gondor".into()]),
+///     Filter::Exists(CharacterAttribute::Spouse, true),
+/// ]);
+/// assert!(filter.try_get_url().is_err());
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Filter {
-    Match(Attribute, Operator, Vec<String>),
-    Exists(Attribute, bool),
+pub enum Filter<A = Attribute> {
+    /// Compares the attribute to the values. There must be at least one value:
+    /// the url of a filter without any ( `name=` ) would compare the attribute
+    /// to an empty string instead, so the request is rejected when it is built.
+    Match(A, Operator, Vec<FilterValue>),
+    Exists(A, bool),
+    /// Matches the attribute against a regular expression, given by its
+    /// pattern and its flags ( only `i` is supported ).
+    /// The operator must be [`Operator::Eq`] or [`Operator::Ne`].
+    ///
+    /// The pattern is a JavaScript regular expression, evaluated by the API. When the
+    /// client evaluates it ( see [`crate::request::plan::Plan`] ), the features that
+    /// the [`regex`] crate lacks, such as lookarounds and backreferences, make it never match.
+    Regex(A, Operator, String, String),
+    /// Matches the items that match all the filters.
+    And(Vec<Filter<A>>),
+    /// Matches the items that match at least one of the filters.
+    Or(Vec<Filter<A>>),
+    /// Matches the items that don't match the filter.
+    Not(Box<Filter<A>>),
 }
 
-impl GetUrl for Filter {
+/// The flags that can be used in a [`Filter::Regex`].
+const REGEX_FLAGS: [char; 1] = ['i'];
+
+/// The url of a filter is the url of its equivalent in the API ( see [`Filter::to_api`] ),
+/// and it is empty if there is none. [`Filter::try_get_url`] returns an error instead.
+impl<A: GetUrl + Copy + PartialEq> GetUrl for Filter<A> {
     fn get_url(&self) -> String {
         match self {
             Filter::Match(attribute, operation, values) => {
                 let mut url = attribute.get_url();
                 url.push_str(&operation.get_url());
-                url.push_str(&values.join(","));
+                let values: Vec<_> = values.iter().map(ToString::to_string).collect();
+                url.push_str(&encoding::encode_list(&values));
                 url
             }
             Filter::Exists(attribute, exists) => {
@@ -44,21 +140,491 @@ impl GetUrl for Filter {
                 url.push_str(&attribute.get_url());
                 url
             }
+            Filter::Regex(attribute, operation, pattern, flags) => {
+                format!(
+                    "{}{}/{}/{}",
+                    attribute.get_url(),
+                    operation.get_url(),
+                    encoding::encode(pattern),
+                    encoding::encode(flags)
+                )
+            }
+            Filter::And(_) | Filter::Or(_) | Filter::Not(_) => match self.to_api() {
+                Some(filters) => filters
+                    .iter()
+                    .map(GetUrl::get_url)
+                    .collect::<Vec<_>>()
+                    .join("&"),
+                None => String::new(),
+            },
+        }
+    }
+}
+
+impl<A: GetUrl + Copy + PartialEq + fmt::Debug> Filter<A> {
+    /// Returns the url of the filter, or an [`Error::UnsupportedFilter`] if the API
+    /// can't express it.
+    ///
+    /// A combination of filters that the API can express is written as the filters the
+    /// API receives, so it is parsed back as these filters ( see [`crate::Request`] ).
+    pub fn try_get_url(&self) -> Result<String, Error> {
+        match self.to_api() {
+            Some(_) => Ok(self.get_url()),
+            None => Err(Error::UnsupportedFilter(format!("{:?}", self))),
+        }
+    }
+}
+
+impl<A: Copy + PartialEq> Filter<A> {
+    /// Returns the filters that the API supports and that, all together, are equivalent
+    /// to this one, or `None` if the API can't express it.
+    ///
+    /// The API only supports a list of filters that must all match, so a [`Filter::Or`]
+    /// is only supported if it compares a single attribute to several values, and a
+    /// [`Filter::Not`] if it can be written with the opposite operator.
+    pub fn to_api(&self) -> Option<Vec<Filter<A>>> {
+        match self {
+            Filter::Match(..) | Filter::Exists(..) | Filter::Regex(..) => Some(vec![self.clone()]),
+            Filter::And(filters) => filters
+                .iter()
+                .map(Filter::to_api)
+                .collect::<Option<Vec<_>>>()
+                .map(|filters| filters.concat()),
+            Filter::Or(filters) => Self::any_of(filters, Operator::Eq),
+            Filter::Not(filter) => filter.negated(),
+        }
+    }
+
+    /// Returns the API equivalent of the negation of this filter.
+    fn negated(&self) -> Option<Vec<Filter<A>>> {
+        let opposite = |operator: &Operator| match operator {
+            Operator::Eq => Some(Operator::Ne),
+            Operator::Ne => Some(Operator::Eq),
+            // `a <= 1` does not match the items without `a`, unlike `!(a > 1)`.
+            _ => None,
+        };
+
+        let filter = match self {
+            Filter::Match(attribute, operator, values) => {
+                Filter::Match(*attribute, opposite(operator)?, values.clone())
+            }
+            Filter::Exists(attribute, exists) => Filter::Exists(*attribute, !exists),
+            Filter::Regex(attribute, operator, pattern, flags) => Filter::Regex(
+                *attribute,
+                opposite(operator)?,
+                pattern.clone(),
+                flags.clone(),
+            ),
+            Filter::Not(filter) => return filter.to_api(),
+            Filter::Or(filters) => return Self::any_of(filters, Operator::Ne),
+            Filter::And(filters) if filters.len() == 1 => return filters[0].negated(),
+            Filter::And(_) => return None,
+        };
+        Some(vec![filter])
+    }
+
+    /// Returns the API equivalent of the items that match any of the filters if the operator
+    /// is [`Operator::Eq`], or none of them if it is [`Operator::Ne`]. It only exists if
+    /// all the filters compare the same attribute for equality, like `realm=Gondor,Rohan`.
+    fn any_of(filters: &[Filter<A>], operator: Operator) -> Option<Vec<Filter<A>>> {
+        if let [filter] = filters {
+            return match operator {
+                Operator::Eq => filter.to_api(),
+                _ => filter.negated(),
+            };
+        }
+
+        let mut attribute = None;
+        let mut values = Vec::new();
+        for filter in filters {
+            let api = filter.to_api();
+            let Some([Filter::Match(a, Operator::Eq, v)]) = api.as_deref() else {
+                return None;
+            };
+            if attribute.is_some_and(|attribute| attribute != *a) {
+                return None;
+            }
+            attribute = Some(*a);
+            values.extend(v.iter().cloned());
+        }
+        Some(vec![Filter::Match(attribute?, operator, values)])
+    }
+}
+
+impl<A: Into<Attribute>> Filter<A> {
+    /// Converts the filter of a specific item into a filter over [`Attribute`].
+    pub(crate) fn into_attribute(self) -> Filter {
+        match self {
+            Filter::Match(attribute, operator, values) => {
+                Filter::Match(attribute.into(), operator, values)
+            }
+            Filter::Exists(attribute, exists) => Filter::Exists(attribute.into(), exists),
+            Filter::Regex(attribute, operator, pattern, flags) => {
+                Filter::Regex(attribute.into(), operator, pattern, flags)
+            }
+            Filter::And(filters) => {
+                Filter::And(filters.into_iter().map(Filter::into_attribute).collect())
+            }
+            Filter::Or(filters) => {
+                Filter::Or(filters.into_iter().map(Filter::into_attribute).collect())
+            }
+            Filter::Not(filter) => Filter::Not(Box::new(filter.into_attribute())),
         }
     }
 }
 
 impl Filter {
-    pub(crate) fn get_item_type(&self) -> ItemType {
+    /// Returns `true` if all the attributes of the filter belong to the given item type.
+    pub(crate) fn is_for(&self, item_type: &ItemType) -> bool {
+        match self {
+            Filter::Match(attribute, _, _)
+            | Filter::Exists(attribute, _)
+            | Filter::Regex(attribute, _, _, _) => &attribute.get_item_type() == item_type,
+            Filter::And(filters) | Filter::Or(filters) => {
+                filters.iter().all(|filter| filter.is_for(item_type))
+            }
+            Filter::Not(filter) => filter.is_for(item_type),
+        }
+    }
+
+    /// Returns `true` if the item, given as the JSON returned by the API, matches the filter.
+    /// It is used for the filters that the API can't express, and to apply requests to
+    /// items in memory ( see [`crate::Request::apply`] ).
+    ///
+    /// An attribute set to `null` is treated as a missing one, like in [`crate::Request::apply`]
+    /// where both are read as `None`.
+    ///
+    /// The filter must be valid, see [`Filter::validate`].
+    pub(crate) fn matches(&self, item: &Value) -> bool {
         match self {
-            Filter::Match(attribute, _, _) => attribute.get_item_type(),
-            Filter::Exists(attribute, _) => attribute.get_item_type(),
+            Filter::Match(attribute, operator, values) => {
+                let value = field(item, attribute);
+                let equals = |expected: &FilterValue| match (value, expected) {
+                    (Some(Value::Number(number)), FilterValue::Number(expected)) => {
+                        number.as_f64() == Some(expected.get())
+                    }
+                    (
+                        Some(Value::String(value)),
+                        FilterValue::String(expected) | FilterValue::Id(expected),
+                    ) => value == expected,
+                    _ => false,
+                };
+                let number = value.and_then(Value::as_f64);
+                let expected = match values.first() {
+                    Some(FilterValue::Number(expected)) => Some(expected.get()),
+                    _ => None,
+                };
+                match (operator, number, expected) {
+                    (Operator::Eq, _, _) => values.iter().any(equals),
+                    (Operator::Ne, _, _) => !values.iter().any(equals),
+                    (Operator::Gt, Some(number), Some(expected)) => number > expected,
+                    (Operator::Lt, Some(number), Some(expected)) => number < expected,
+                    (Operator::Gte, Some(number), Some(expected)) => number >= expected,
+                    (Operator::Lte, Some(number), Some(expected)) => number <= expected,
+                    _ => false,
+                }
+            }
+            Filter::Exists(attribute, exists) => field(item, attribute).is_some() == *exists,
+            Filter::Regex(attribute, operator, pattern, flags) => {
+                let is_match = match (field(item, attribute), regex(pattern, flags)) {
+                    (Some(Value::String(value)), Some(regex)) => regex.is_match(value),
+                    _ => false,
+                };
+                is_match == (*operator == Operator::Eq)
+            }
+            Filter::And(filters) => filters.iter().all(|filter| filter.matches(item)),
+            Filter::Or(filters) => filters.iter().any(|filter| filter.matches(item)),
+            Filter::Not(filter) => !filter.matches(item),
+        }
+    }
+
+    /// Checks that the filter is valid, for the API or for the client.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match self {
+            Filter::Regex(_, operator, pattern, flags) => {
+                if !matches!(operator, Operator::Eq | Operator::Ne) {
+                    return Err(Error::InvalidRegex {
+                        pattern: pattern.clone(),
+                        reason: format!("the operator {} can't be used", operator.get_url()),
+                    });
+                }
+                check_regex(pattern, flags)
+            }
+            Filter::Match(attribute, operator, values) => {
+                // A value can't be greater than several values at once.
+                let max_values = if operator.is_comparison() {
+                    1
+                } else {
+                    usize::MAX
+                };
+                if values.is_empty() || values.len() > max_values {
+                    return Err(Error::InvalidValueCount {
+                        attribute: *attribute,
+                        operator: *operator,
+                        count: values.len(),
+                    });
+                }
+                let kind = attribute.value_kind();
+                if operator.is_comparison() && kind != ValueKind::Number {
+                    return Err(Error::InvalidOperator {
+                        attribute: *attribute,
+                        operator: *operator,
+                    });
+                }
+                for value in values {
+                    match value {
+                        FilterValue::Id(id) if kind == ValueKind::Id => id::validate(id)?,
+                        value if value.kind() != kind => {
+                            return Err(Error::InvalidFilterValue {
+                                attribute: *attribute,
+                                value: value.clone(),
+                                expected: kind,
+                            })
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(())
+            }
+            Filter::Exists(_, _) => Ok(()),
+            Filter::And(filters) | Filter::Or(filters) => {
+                if filters.is_empty() {
+                    return Err(Error::InvalidFilter);
+                }
+                filters.iter().try_for_each(Filter::validate)
+            }
+            Filter::Not(filter) => filter.validate(),
         }
     }
 }
 
-/// The operator used to compare the attribute and the values.
+/// Returns the value of the attribute in the item, if it is set.
+fn field<'a>(item: &'a Value, attribute: &Attribute) -> Option<&'a Value> {
+    item.get(attribute.get_url())
+        .filter(|value| !value.is_null())
+}
+
+/// Checks what the API can't take in the regular expression of a [`Filter::Regex`]:
+/// unsupported or duplicated flags and unbalanced delimiters. The rest of the
+/// syntax is left to the API.
+pub(crate) fn check_regex(pattern: &str, flags: &str) -> Result<(), Error> {
+    let invalid = |reason: String| {
+        Err(Error::InvalidRegex {
+            pattern: pattern.to_string(),
+            reason,
+        })
+    };
+
+    for (index, flag) in flags.char_indices() {
+        if !REGEX_FLAGS.contains(&flag) {
+            return invalid(format!("unsupported flag '{}'", flag));
+        }
+        if flags[..index].contains(flag) {
+            return invalid(format!("duplicated flag '{}'", flag));
+        }
+    }
+
+    let mut groups = 0usize;
+    let mut in_class = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // The guard skips the escaped character.
+            '\\' if chars.next().is_none() => {
+                return invalid("trailing backslash".to_string());
+            }
+            ']' if in_class => in_class = false,
+            _ if in_class => {}
+            '[' => in_class = true,
+            '(' => groups += 1,
+            ')' => match groups.checked_sub(1) {
+                Some(open) => groups = open,
+                None => return invalid("unopened group".to_string()),
+            },
+            _ => {}
+        }
+    }
+    if in_class {
+        return invalid("unclosed character class".to_string());
+    }
+    if groups > 0 {
+        return invalid("unclosed group".to_string());
+    }
+    Ok(())
+}
+
+/// Compiles the regular expression of a [`Filter::Regex`] to evaluate it on the client,
+/// or returns `None` if it uses a feature that the [`regex`] crate lacks.
+fn regex(pattern: &str, flags: &str) -> Option<regex::Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(flags.contains('i'))
+        .build()
+        .ok()
+}
+
+/// The kind of the values of an attribute, which is the kind of the [`FilterValue`]s
+/// that it can be compared to.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueKind {
+    Number,
+    String,
+    /// The id of an item, like the `_id` attributes or the references to other items.
+    Id,
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueKind::Number => write!(formatter, "number"),
+            ValueKind::String => write!(formatter, "string"),
+            ValueKind::Id => write!(formatter, "id"),
+        }
+    }
+}
+
+/// A value that an attribute is compared to in a [`Filter::Match`].
+///
+/// Strings and integers can be converted into a value with `into()`, and floats
+/// with `try_from()`, which rejects the numbers that are not finite.
+/// Ids are created with [`FilterValue::id`].
+///
+/// # Example
+/// ```
+/// use lotr_api::filter::{FilterValue, ValueKind};
+///
+/// let value: FilterValue = "Gondor".into();
+/// assert_eq!(value.kind(), ValueKind::String);
+///
+/// let value: FilterValue = 100.into();
+/// assert_eq!(value.kind(), ValueKind::Number);
+///
+/// let value = FilterValue::id("5cd99d4bde30eff6ebccfea0");
+/// assert_eq!(value.kind(), ValueKind::Id);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterValue {
+    Number(Number),
+    String(String),
+    Id(String),
+}
+
+/// A finite number, the value of a [`FilterValue::Number`].
+///
+/// # Example
+/// ```
+/// use lotr_api::filter::{FilterValue, Number};
+///
+/// assert_eq!(Number::new(93.5).unwrap().get(), 93.5);
+/// assert!(Number::new(f64::NAN).is_err());
+/// assert!(FilterValue::try_from(f64::INFINITY).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Number(f64);
+
+// Only finite numbers can be created, so a number is always equal to itself.
+impl Eq for Number {}
+
+impl Number {
+    /// Creates a number. An [`Error::InvalidNumber`] is returned if it is NaN or infinite.
+    pub fn new(number: f64) -> Result<Self, Error> {
+        if number.is_finite() {
+            Ok(Self(number))
+        } else {
+            Err(Error::InvalidNumber(number))
+        }
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.0)
+    }
+}
+
+impl FilterValue {
+    /// Creates a value holding the id of an item. The id is checked when the
+    /// request is built: it must be made of 24 hexadecimal digits.
+    pub fn id(id: impl Into<String>) -> Self {
+        FilterValue::Id(id.into())
+    }
+
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            FilterValue::Number(_) => ValueKind::Number,
+            FilterValue::String(_) => ValueKind::String,
+            FilterValue::Id(_) => ValueKind::Id,
+        }
+    }
+
+    /// Returns the value written as `value` in the url of an attribute of the given kind.
+    /// A number that can't be parsed, or that is not finite, is kept as a string,
+    /// so that it is rejected when the request is validated.
+    pub(crate) fn from_url(kind: ValueKind, value: String) -> Self {
+        match kind {
+            ValueKind::Number => match value.parse().map(Number::new) {
+                Ok(Ok(number)) => FilterValue::Number(number),
+                _ => FilterValue::String(value),
+            },
+            ValueKind::String => FilterValue::String(value),
+            ValueKind::Id => FilterValue::Id(value),
+        }
+    }
+}
+
+impl fmt::Display for FilterValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterValue::Number(number) => write!(formatter, "{}", number),
+            FilterValue::String(value) | FilterValue::Id(value) => write!(formatter, "{}", value),
+        }
+    }
+}
+
+impl From<&str> for FilterValue {
+    fn from(value: &str) -> Self {
+        FilterValue::String(value.to_string())
+    }
+}
+
+impl From<String> for FilterValue {
+    fn from(value: String) -> Self {
+        FilterValue::String(value)
+    }
+}
+
+impl From<Number> for FilterValue {
+    fn from(value: Number) -> Self {
+        FilterValue::Number(value)
+    }
+}
+
+impl TryFrom<f64> for FilterValue {
+    type Error = Error;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Number::new(value).map(FilterValue::Number)
+    }
+}
+
+impl From<i32> for FilterValue {
+    fn from(value: i32) -> Self {
+        FilterValue::Number(Number(value.into()))
+    }
+}
+
+impl From<u32> for FilterValue {
+    fn from(value: u32) -> Self {
+        FilterValue::Number(Number(value.into()))
+    }
+}
+
+/// The operator used to compare the attribute and the values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Operator {
     /// Equal to
     Eq,
@@ -74,6 +640,16 @@ pub enum Operator {
     Lte,
 }
 
+impl Operator {
+    /// Returns `true` for the operators that compare numbers.
+    pub(crate) fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::Gt | Operator::Lt | Operator::Gte | Operator::Lte
+        )
+    }
+}
+
 impl GetUrl for Operator {
     fn get_url(&self) -> String {
         match self {
@@ -99,11 +675,11 @@ mod tests {
         let filter_eq = Filter::Match(
             Attribute::Book(BookAttribute::Name),
             Operator::Eq,
-            vec!["The Fellowship of the Ring".to_string()],
+            vec!["The Fellowship of the Ring".into()],
         );
         assert_eq!(
             filter_eq.get_url(),
-            "name=The Fellowship of the Ring".to_string()
+            "name=The%20Fellowship%20of%20the%20Ring".to_string()
         );
     }
 
@@ -112,11 +688,11 @@ mod tests {
         let filter_ne = Filter::Match(
             Attribute::Book(BookAttribute::Name),
             Operator::Ne,
-            vec!["The Fellowship of the Ring".to_string()],
+            vec!["The Fellowship of the Ring".into()],
         );
         assert_eq!(
             filter_ne.get_url(),
-            "name!=The Fellowship of the Ring".to_string()
+            "name!=The%20Fellowship%20of%20the%20Ring".to_string()
         );
     }
 
@@ -138,30 +714,202 @@ mod tests {
             Attribute::Book(BookAttribute::Name),
             Operator::Eq,
             vec![
-                "The Fellowship Of The Ring".to_string(),
-                "The Two Towers".to_string(),
-                "The Return Of The King".to_string(),
+                "The Fellowship Of The Ring".into(),
+                "The Two Towers".into(),
+                "The Return Of The King".into(),
             ],
         );
 
         assert_eq!(
             filter.get_url(),
-            "name=The Fellowship Of The Ring,The Two Towers,The Return Of The King".to_string()
+            "name=The%20Fellowship%20Of%20The%20Ring,The%20Two%20Towers,The%20Return%20Of%20The%20King".to_string()
         );
 
         let filter = Filter::Match(
             Attribute::Book(BookAttribute::Name),
             Operator::Ne,
             vec![
-                "The Fellowship Of The Ring".to_string(),
-                "The Two Towers".to_string(),
-                "The Return Of The King".to_string(),
+                "The Fellowship Of The Ring".into(),
+                "The Two Towers".into(),
+                "The Return Of The King".into(),
             ],
         );
 
         assert_eq!(
             filter.get_url(),
-            "name!=The Fellowship Of The Ring,The Two Towers,The Return Of The King".to_string()
+            "name!=The%20Fellowship%20Of%20The%20Ring,The%20Two%20Towers,The%20Return%20Of%20The%20King".to_string()
+        );
+    }
+
+    #[test]
+    fn test_regex() {
+        let filter = Filter::Regex(
+            Attribute::Character(CharacterAttribute::Name),
+            Operator::Eq,
+            "foot".to_string(),
+            "i".to_string(),
+        );
+        assert_eq!(filter.get_url(), "name=/foot/i");
+        assert!(filter.validate().is_ok());
+
+        let filter = Filter::Regex(
+            Attribute::Character(CharacterAttribute::Name),
+            Operator::Ne,
+            "^Frodo".to_string(),
+            String::new(),
+        );
+        assert_eq!(filter.get_url(), "name!=/%5EFrodo/");
+        assert!(filter.validate().is_ok());
+    }
+
+    #[test]
+    fn test_encoded_values() {
+        let filter = Filter::Match(
+            Attribute::Character(CharacterAttribute::Name),
+            Operator::Eq,
+            vec!["Éowyn".into(), "Salt & Pepper, #1".into()],
+        );
+        assert_eq!(
+            filter.get_url(),
+            "name=%C3%89owyn,Salt%20%26%20Pepper%2C%20%231"
+        );
+
+        let filter = Filter::Regex(
+            Attribute::Character(CharacterAttribute::Name),
+            Operator::Eq,
+            "a/b|c+".to_string(),
+            String::new(),
+        );
+        assert_eq!(filter.get_url(), "name=/a%2Fb%7Cc%2B/");
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        let tests = vec![
+            (Operator::Gt, "foot", ""),
+            (Operator::Eq, "(foot", ""),
+            (Operator::Eq, "foot", "g"),
+            (Operator::Eq, "foot", "ii"),
+            (Operator::Eq, "foot", "m"),
+            (Operator::Eq, "foot)", ""),
+            (Operator::Eq, "[foot", ""),
+            (Operator::Eq, "foot\\", ""),
+        ];
+
+        for (operator, pattern, flags) in tests {
+            let filter = Filter::Regex(
+                Attribute::Character(CharacterAttribute::Name),
+                operator,
+                pattern.to_string(),
+                flags.to_string(),
+            );
+            assert!(filter.validate().is_err(), "{:?} should be invalid", filter);
+        }
+    }
+
+    #[test]
+    fn test_javascript_regex() {
+        // Valid for the API, even though the regex crate can't compile some of them.
+        let patterns = vec!["(?<=Bag)gins", "^(?!Frodo)", "(a)\\1", "[(]", "\\(foot\\)"];
+
+        for pattern in patterns {
+            let filter = Filter::Regex(
+                Attribute::Character(CharacterAttribute::Name),
+                Operator::Eq,
+                pattern.to_string(),
+                "i".to_string(),
+            );
+            assert!(filter.validate().is_ok(), "{:?} should be valid", filter);
+        }
+    }
+
+    #[test]
+    fn test_value_kinds() {
+        let valid = vec![
+            Filter::Match(
+                Attribute::Movie(MovieAttribute::BudgetInMillions),
+                Operator::Lte,
+                vec![FilterValue::try_from(93.5).unwrap()],
+            ),
+            Filter::Match(
+                Attribute::Movie(MovieAttribute::Name),
+                Operator::Eq,
+                vec!["The Two Towers".into()],
+            ),
+            Filter::Match(
+                Attribute::Book(BookAttribute::Id),
+                Operator::Ne,
+                vec![FilterValue::id("5cf5805fb53e011a64671582")],
+            ),
+        ];
+        for filter in valid {
+            assert!(filter.validate().is_ok(), "{:?} should be valid", filter);
+        }
+
+        let filter = Filter::Match(
+            Attribute::Book(BookAttribute::Name),
+            Operator::Gt,
+            vec!["The Two Towers".into()],
+        );
+        assert!(matches!(
+            filter.validate(),
+            Err(Error::InvalidOperator {
+                attribute: Attribute::Book(BookAttribute::Name),
+                operator: Operator::Gt
+            })
+        ));
+
+        let invalid_values = vec![
+            (MovieAttribute::BudgetInMillions.into(), "abc".into()),
+            (MovieAttribute::Name.into(), 10.into()),
+            (BookAttribute::Id.into(), "5cf5805fb53e011a64671582".into()),
+            (
+                BookAttribute::Name.into(),
+                FilterValue::id("5cf5805fb53e011a64671582"),
+            ),
+        ];
+        for (attribute, value) in invalid_values {
+            let filter = Filter::Match(attribute, Operator::Eq, vec![value]);
+            assert!(
+                matches!(filter.validate(), Err(Error::InvalidFilterValue { .. })),
+                "{:?} should be invalid",
+                filter
+            );
+        }
+
+        for id in ["", "abc", "5cf5805fb53e011a6467158g"] {
+            let filter = Filter::Match(
+                Attribute::Book(BookAttribute::Id),
+                Operator::Eq,
+                vec![FilterValue::id(id)],
+            );
+            assert!(
+                matches!(filter.validate(), Err(Error::InvalidId(ref invalid)) if invalid == id),
+                "{:?} should be invalid",
+                filter
+            );
+        }
+
+        let filter = Filter::Match(
+            Attribute::Movie(MovieAttribute::BudgetInMillions),
+            Operator::Gt,
+            vec![10.into(), 20.into()],
+        );
+        assert!(matches!(
+            filter.validate(),
+            Err(Error::InvalidValueCount { count: 2, .. })
+        ));
+
+        let filter = Filter::Match(Attribute::Movie(MovieAttribute::Name), Operator::Ne, vec![]);
+        assert!(matches!(
+            filter.validate(),
+            Err(Error::InvalidValueCount { count: 0, .. })
+        ));
+
+        assert!(FilterValue::try_from(f64::NAN).is_err());
+        assert_eq!(
+            FilterValue::from_url(ValueKind::Number, "inf".to_string()),
+            FilterValue::String("inf".to_string())
         );
     }
 
@@ -172,7 +920,7 @@ mod tests {
                 Filter::Match(
                     Attribute::Movie(MovieAttribute::BudgetInMillions),
                     Operator::Gt,
-                    vec!["10".to_string()],
+                    vec![10.into()],
                 ),
                 "budgetInMillions>10",
             ),
@@ -180,7 +928,7 @@ mod tests {
                 Filter::Match(
                     Attribute::Movie(MovieAttribute::BudgetInMillions),
                     Operator::Gte,
-                    vec!["10".to_string()],
+                    vec![10.into()],
                 ),
                 "budgetInMillions>=10",
             ),
@@ -188,7 +936,7 @@ mod tests {
                 Filter::Match(
                     Attribute::Movie(MovieAttribute::BudgetInMillions),
                     Operator::Lt,
-                    vec!["10".to_string()],
+                    vec![10.into()],
                 ),
                 "budgetInMillions<10",
             ),
@@ -196,7 +944,7 @@ mod tests {
                 Filter::Match(
                     Attribute::Movie(MovieAttribute::BudgetInMillions),
                     Operator::Lte,
-                    vec!["10".to_string()],
+                    vec![10.into()],
                 ),
                 "budgetInMillions<=10",
             ),
@@ -206,4 +954,122 @@ mod tests {
             assert_eq!(filter.get_url(), expected.to_string());
         }
     }
+
+    #[test]
+    fn test_combinators_url() {
+        let realm = |realm: &str| {
+            Filter::Match(CharacterAttribute::Realm, Operator::Eq, vec![realm.into()])
+        };
+
+        let filter = Filter::Or(vec![realm("Gondor"), realm("Rohan")]);
+        assert_eq!(filter.get_url(), "realm=Gondor,Rohan");
+
+        let filter = Filter::And(vec![
+            Filter::Not(Box::new(realm("Gondor"))),
+            Filter::Not(Box::new(Filter::Exists(CharacterAttribute::Spouse, false))),
+        ]);
+        assert_eq!(filter.get_url(), "realm!=Gondor&spouse");
+
+        let filter = Filter::Or(vec![
+            realm("Gondor"),
+            Filter::Match(
+                CharacterAttribute::Gender,
+                Operator::Eq,
+                vec!["Female".into()],
+            ),
+        ]);
+        assert_eq!(filter.to_api(), None);
+        assert_eq!(filter.get_url(), "");
+
+        let filter = Filter::Not(Box::new(Filter::And(vec![realm("Gondor"), realm("Rohan")])));
+        assert_eq!(filter.to_api(), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let movie = serde_json::json!({
+            "name": "The Two Towers",
+            "budgetInMillions": 94,
+            "academyAwardWins": 2,
+            "rottenTomatoesScore": null,
+        });
+        let name = Attribute::Movie(MovieAttribute::Name);
+        let budget = Attribute::Movie(MovieAttribute::BudgetInMillions);
+        let score = Attribute::Movie(MovieAttribute::RottenTomatoesScore);
+        let runtime = Attribute::Movie(MovieAttribute::RuntimeInMinutes);
+
+        let tests = vec![
+            (
+                Filter::Match(name, Operator::Eq, vec!["The Two Towers".into()]),
+                true,
+            ),
+            (
+                Filter::Match(
+                    name,
+                    Operator::Eq,
+                    vec!["a".into(), "The Two Towers".into()],
+                ),
+                true,
+            ),
+            (
+                Filter::Match(name, Operator::Ne, vec!["The Two Towers".into()]),
+                false,
+            ),
+            (Filter::Match(budget, Operator::Eq, vec![94.into()]), true),
+            (Filter::Match(budget, Operator::Gt, vec![94.into()]), false),
+            (Filter::Match(budget, Operator::Gte, vec![94.into()]), true),
+            (Filter::Match(budget, Operator::Lt, vec![100.into()]), true),
+            (Filter::Match(budget, Operator::Lte, vec![90.into()]), false),
+            (
+                Filter::Match(runtime, Operator::Lte, vec![90.into()]),
+                false,
+            ),
+            (Filter::Match(runtime, Operator::Ne, vec![90.into()]), true),
+            (Filter::Exists(name, true), true),
+            (Filter::Exists(score, true), false),
+            (Filter::Exists(runtime, true), false),
+            (Filter::Exists(runtime, false), true),
+            (
+                Filter::Regex(name, Operator::Eq, "two".to_string(), "i".to_string()),
+                true,
+            ),
+            (
+                Filter::Regex(name, Operator::Ne, "two".to_string(), String::new()),
+                true,
+            ),
+            (
+                Filter::Or(vec![
+                    Filter::Exists(runtime, true),
+                    Filter::Match(budget, Operator::Gt, vec![90.into()]),
+                ]),
+                true,
+            ),
+            (
+                Filter::And(vec![
+                    Filter::Exists(runtime, true),
+                    Filter::Match(budget, Operator::Gt, vec![90.into()]),
+                ]),
+                false,
+            ),
+            (Filter::Not(Box::new(Filter::Exists(runtime, true))), true),
+        ];
+
+        for (filter, expected) in tests {
+            assert_eq!(filter.matches(&movie), expected, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn test_validate_combinators() {
+        let filter: Filter = Filter::Or(vec![]);
+        assert!(matches!(filter.validate(), Err(Error::InvalidFilter)));
+
+        let filter = Filter::Not(Box::new(Filter::And(vec![Filter::Regex(
+            Attribute::Character(CharacterAttribute::Name),
+            Operator::Eq,
+            "[".to_string(),
+            String::new(),
+        )])));
+        assert!(matches!(filter.validate(), Err(Error::InvalidRegex { .. })));
+    }
 }
@@ -2,17 +2,46 @@
 //! Here we define the [`Request`] struct and the [`RequestBuilder`] struct, which
 //! are the center of the custom request system.
 
-use reqwest::header::{self, HeaderMap, HeaderValue};
+use std::{fmt, marker::PhantomData, sync::Arc};
 
-use crate::{Error, ItemType};
+use reqwest::header::HeaderMap;
+use serde_json::Value;
 
-use self::{filter::Filter, pagination::Pagination, sort::Sort};
+use crate::{
+    attribute::{Attribute, ChapterAttribute, QuoteAttribute},
+    item::{
+        id::{self, Id},
+        TypedItem,
+    },
+    transport::{Transport, TransportRequest},
+    Error, Item, ItemType,
+};
+
+use self::{
+    filter::{Filter, FilterValue, Operator},
+    page::Page,
+    pagination::Pagination,
+    plan::Plan,
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+    sort::Sort,
+};
 
 pub mod attributes;
+pub mod decode;
+mod encoding;
 pub mod filter;
+pub mod page;
 pub mod pagination;
+mod parse;
+pub mod plan;
+pub mod rate_limit;
+pub mod retry;
+mod serialization;
 pub mod sort;
 
+pub use self::serialization::FORMAT_VERSION;
+
 /// This trait is implemented by all structs that can be used to make a request to the API.
 /// It is used to get the url for the request.
 pub trait GetUrl {
@@ -40,20 +69,55 @@ pub trait GetUrl {
 ///
 /// assert_eq!(request.get_url(), "book?sort=name:asc");
 /// ```
-pub struct RequestBuilder {
+///
+/// The builder can also be typed with the item it returns, see [`RequestBuilder::typed`].
+pub struct RequestBuilder<T = Item> {
     request: Request,
+    /// The item type of the id set with [`RequestBuilder::id`], checked when the request is built.
+    id_item_type: Option<ItemType>,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> RequestBuilder<T> {
+    /// Sets the pagination of the request.
+    ///
+    /// An empty pagination ( `Pagination::new(0, 0, 0)` ) has no url, so it is the
+    /// same as no pagination and the request is left without one. This keeps the
+    /// request equal to the one parsed back from its url.
+    pub fn pagination(mut self, pagination: Pagination) -> Self {
+        self.request.pagination = (!pagination.is_empty()).then_some(pagination);
+        self
+    }
 }
 
 impl RequestBuilder {
     pub fn new(item_type: ItemType) -> Self {
         Self {
             request: Request::new(item_type),
+            id_item_type: None,
+            _item: PhantomData,
         }
     }
 
     /// Sets the id of the request. This is used to get a specific item.
-    pub fn id(mut self, id: String) -> Self {
-        self.request.id = Some(id);
+    /// The id must be the id of an item of the item type of the request,
+    /// if not the `build` function will return an [`Error::MismatchedId`].
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::{BookId, ItemType, MovieId, RequestBuilder,
+    ///     request::GetUrl};
+    ///
+    /// let id: BookId = "5cf5805fb53e011a64671582".parse().unwrap();
+    /// let request = RequestBuilder::new(ItemType::Book).id(id).build().unwrap();
+    /// assert_eq!(request.get_url(), "book/5cf5805fb53e011a64671582");
+    ///
+    /// let id: MovieId = "5cd95395de30eff6ebccde5d".parse().unwrap();
+    /// assert!(RequestBuilder::new(ItemType::Book).id(id).build().is_err());
+    /// ```
+    pub fn id<I: Id>(mut self, id: I) -> Self {
+        self.request.id = Some(id.into());
+        self.id_item_type = Some(I::item_type());
         self
     }
 
@@ -63,16 +127,17 @@ impl RequestBuilder {
     ///
     /// # Example
     /// ```
-    /// use lotr_api::{ItemType, Request, RequestBuilder,
+    /// use lotr_api::{CharacterId, ItemType, Request, RequestBuilder,
     ///     request::GetUrl};
     ///
+    /// let id: CharacterId = "5cd99d4bde30eff6ebccfbe6".parse().unwrap();
     /// let request = RequestBuilder::new(ItemType::Character)
-    ///     .id("123".to_string())
+    ///     .id(id)
     ///     .secondary_item_type(ItemType::Quote)
     ///     .build()
     ///     .unwrap();
     ///
-    /// assert_eq!(request.get_url(), "character/123/quote");
+    /// assert_eq!(request.get_url(), "character/5cd99d4bde30eff6ebccfbe6/quote");
     ///   ```
     ///
     pub fn secondary_item_type(mut self, secondary_item_type: ItemType) -> Self {
@@ -80,14 +145,19 @@ impl RequestBuilder {
         self
     }
 
-    /// Sets the sort of the request. If you wish to sort the results
+    /// Adds a sort to the request. If you wish to sort the results
     /// of the request, the `sort_by` attribute of the `Sort` struct
     /// must be of the same type as the item type of the request ( or the
     /// secondary item type if it is set).
     ///
+    /// It can be called several times to sort by several attributes, the first
+    /// sort being the primary key. The url holds every sort, but the API only sorts
+    /// by one attribute: the [`crate::Client`] only sends the first sort and applies
+    /// the others itself ( see [`crate::Client::get_page`] ).
+    ///
     /// # Example
     /// ```
-    /// use lotr_api::{ItemType, Request, RequestBuilder,
+    /// use lotr_api::{CharacterId, ItemType, Request, RequestBuilder,
     ///     attribute::{Attribute, BookAttribute},
     ///     request::GetUrl,
     ///     sort::{Sort, SortOrder}};
@@ -104,13 +174,13 @@ impl RequestBuilder {
     /// ```
     /// Failing to match the item type of the request results in an error.
     /// ```
-    /// use lotr_api::{ItemType, Request, RequestBuilder,
+    /// use lotr_api::{CharacterId, ItemType, Request, RequestBuilder,
     ///     attribute::{Attribute, BookAttribute},
     ///     request::GetUrl,
     ///     sort::{ Sort, SortOrder}};
     ///
     ///  let request = RequestBuilder::new(ItemType::Character)
-    ///     .id("123".to_string())
+    ///     .id(CharacterId::new("5cd99d4bde30eff6ebccfbe6").unwrap())
     ///     .secondary_item_type(ItemType::Quote)
     ///     .sort(Sort::new(
     ///         SortOrder::Ascending,
@@ -121,19 +191,46 @@ impl RequestBuilder {
     /// assert!(request.is_err());
     /// ```
     ///
+    /// With several sorts:
+    ///
+    /// ```
+    /// use lotr_api::{ItemType, Request, RequestBuilder,
+    ///     attribute::{Attribute, CharacterAttribute},
+    ///     request::GetUrl,
+    ///     sort::{Sort, SortOrder}};
+    ///
+    /// let request = RequestBuilder::new(ItemType::Character)
+    ///     .sort(Sort::new(
+    ///         SortOrder::Ascending,
+    ///         Attribute::Character(CharacterAttribute::Realm),
+    ///     ))
+    ///     .sort(Sort::new(
+    ///         SortOrder::Ascending,
+    ///         Attribute::Character(CharacterAttribute::Name),
+    ///     ))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.get_url(), "character?sort=realm:asc&sort=name:asc");
+    /// assert_eq!(request.plan().local_sorts().len(), 1);
+    /// ```
     pub fn sort(mut self, sort: Sort) -> Self {
-        self.request.sort = Some(sort);
+        self.request.sorts.push(sort);
         self
     }
 
-    /// Sets the filter of the request. If you wish to filter the results
+    /// Adds a filter to the request. If you wish to filter the results
     /// of the request, the `filter_by` attribute of the `Filter` struct
     /// must be of the same type as the item type of the request ( or the
     /// secondary item type if it is set).
     ///
+    /// It can be called several times, the request then returns the items
+    /// that match all the filters. The filters that the API can't express, like most
+    /// [`Filter::Or`], are evaluated by the [`crate::Client`] ( see [`Plan`] ).
+    ///
     /// # Example
     /// ```
-    /// use lotr_api::{ItemType, Request, RequestBuilder,
+    /// use lotr_api::{CharacterId, ItemType, Request, RequestBuilder,
     ///     attribute::{Attribute, BookAttribute},
     ///     request::GetUrl,
     ///     filter::{Filter, Operator}};
@@ -142,42 +239,61 @@ impl RequestBuilder {
     ///     .filter(Filter::Match(
     ///         Attribute::Book(BookAttribute::Name),
     ///         Operator::Eq,
-    ///         vec!["The Fellowship of the Ring".to_string()],
+    ///         vec!["The Fellowship of the Ring".into()],
     ///     ))
     ///     .build()
     ///     .unwrap();
     ///
-    /// assert_eq!(request.get_url(), "book?name=The Fellowship of the Ring");
+    /// assert_eq!(request.get_url(), "book?name=The%20Fellowship%20of%20the%20Ring");
     /// ```
     ///
-    /// Failing to match the item type of the request results in an error.
+    /// With several filters:
     ///
     /// ```
     /// use lotr_api::{ItemType, Request, RequestBuilder,
+    ///     attribute::{Attribute, CharacterAttribute},
+    ///     request::GetUrl,
+    ///     filter::{Filter, Operator}};
+    ///
+    /// let request = RequestBuilder::new(ItemType::Character)
+    ///     .filter(Filter::Match(
+    ///         Attribute::Character(CharacterAttribute::Realm),
+    ///         Operator::Eq,
+    ///         vec!["Gondor".into()],
+    ///     ))
+    ///     .filter(Filter::Match(
+    ///         Attribute::Character(CharacterAttribute::Gender),
+    ///         Operator::Ne,
+    ///         vec!["Male".into()],
+    ///     ))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.get_url(), "character?realm=Gondor&gender!=Male");
+    /// ```
+    ///
+    /// Failing to match the item type of the request results in an error.
+    ///
+    /// ```
+    /// use lotr_api::{CharacterId, ItemType, Request, RequestBuilder,
     ///     attribute::{Attribute, BookAttribute},
     ///     request::GetUrl,
     ///     filter::{Filter, Operator}};
     ///
     /// let request = RequestBuilder::new(ItemType::Character)
-    ///     .id("123".to_string())
+    ///     .id(CharacterId::new("5cd99d4bde30eff6ebccfbe6").unwrap())
     ///     .secondary_item_type(ItemType::Quote)
     ///     .filter(Filter::Match(
     ///         Attribute::Book(BookAttribute::Name),
     ///         Operator::Eq,
-    ///         vec!["The Fellowship of the Ring".to_string()],
+    ///         vec!["The Fellowship of the Ring".into()],
     ///     ))
     ///     .build();
     ///
     /// assert!(request.is_err());
     /// ```
     pub fn filter(mut self, filter: Filter) -> Self {
-        self.request.filter = Some(filter);
-        self
-    }
-
-    /// Sets the pagination of the request.
-    pub fn pagination(mut self, pagination: Pagination) -> Self {
-        self.request.pagination = Some(pagination);
+        self.request.filters.push(filter);
         self
     }
 
@@ -186,39 +302,231 @@ impl RequestBuilder {
     /// # Errors
     ///
     /// A request is invalid if:
+    /// - The id is the id of an item of another type ( [`Error::MismatchedId`] ).
     /// - The secondary item type is set but the id is not.
-    /// - The sort is set but the item type of the sort does not match the item type of the request.
-    /// - The filter is set but the item type of the filter does not match the item type of the request.
+    /// - The API has no route for the item type and the secondary item type
+    ///   ( [`Error::UnsupportedSecondaryItemType`] ). The supported ones are
+    ///   `book/{id}/chapter`, `movie/{id}/quote` and `character/{id}/quote`.
+    /// - The item type of one of the sorts does not match the item type of the request.
+    /// - The item type of one of the filters does not match the item type of the request.
+    /// - One of the filters is a regular expression that is not valid.
+    /// - One of the filters compares an attribute that is not a number with [`filter::Operator::Gt`],
+    ///   [`filter::Operator::Lt`], [`filter::Operator::Gte`] or [`filter::Operator::Lte`]
+    ///   ( [`Error::InvalidOperator`] ).
+    /// - One of the values of a filter is not of the kind of its attribute ( [`Error::InvalidFilterValue`] ).
+    /// - One of the ids a filter compares an attribute to is not valid ( [`Error::InvalidId`] ).
     pub fn build(self) -> Result<Request, Error> {
-        let item_type = self.request.get_item_type();
-        if let Some(sort) = &self.request.sort {
-            if sort.get_item_type() != item_type {
-                return Err(Error::InvalidSort);
+        if let Some(found) = self.id_item_type {
+            if found != self.request.item_type {
+                return Err(Error::MismatchedId {
+                    expected: self.request.item_type,
+                    found,
+                });
             }
         }
-        if let Some(filter) = &self.request.filter {
-            if filter.get_item_type() != item_type {
-                return Err(Error::InvalidFilter);
-            }
-        }
-        // Every secondary item type needs an id.
-        if self.request.secondary_item_type.is_some() && self.request.id.is_none() {
-            return Err(Error::InvalidSecondaryItemType);
+        self.request.validate()
+    }
+}
+
+impl<T: TypedItem> RequestBuilder<T> {
+    /// Creates a builder for a request that returns items of type `T`.
+    /// The sorts and filters of the request only accept the attributes of `T`,
+    /// and the [`crate::Client`] returns the results as `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::{Character, RequestBuilder,
+    ///     attribute::CharacterAttribute,
+    ///     filter::{Filter, Operator},
+    ///     request::GetUrl,
+    ///     sort::{Sort, SortOrder}};
+    ///
+    /// let request = RequestBuilder::<Character>::typed()
+    ///     .filter(Filter::Match(
+    ///         CharacterAttribute::Realm,
+    ///         Operator::Eq,
+    ///         vec!["Gondor".into()],
+    ///     ))
+    ///     .sort(Sort::new(SortOrder::Ascending, CharacterAttribute::Name))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.get_url(), "character?sort=name:asc&realm=Gondor");
+    /// ```
+    ///
+    /// Using the attribute of another item does not compile.
+    ///
+    /// ```compile_fail
+    /// use lotr_api::{Character, RequestBuilder,
+    ///     attribute::BookAttribute,
+    ///     sort::{Sort, SortOrder}};
+    ///
+    /// let request = RequestBuilder::<Character>::typed()
+    ///     .sort(Sort::new(SortOrder::Ascending, BookAttribute::Name));
+    /// ```
+    pub fn typed() -> Self {
+        Self {
+            request: Request::new(T::item_type()),
+            id_item_type: None,
+            _item: PhantomData,
         }
+    }
 
-        Ok(self.request)
+    /// Sets the id of the request. This is used to get a specific item.
+    /// It replaces the parent set with [`RequestBuilder::parent`].
+    pub fn id(mut self, id: T::Id) -> Self {
+        self.request.item_type = T::item_type();
+        self.request.id = Some(id.into());
+        self.request.secondary_item_type = None;
+        self
+    }
+
+    /// Restricts the request to the items that belong to the item with the given id,
+    /// for example the quotes of a character. It replaces the id set with [`RequestBuilder::id`].
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::{CharacterId, Quote, RequestBuilder,
+    ///     request::GetUrl};
+    ///
+    /// let id: CharacterId = "5cd99d4bde30eff6ebccfbe6".parse().unwrap();
+    /// let request = RequestBuilder::<Quote>::typed()
+    ///     .parent(id)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(request.get_url(), "character/5cd99d4bde30eff6ebccfbe6/quote");
+    /// ```
+    pub fn parent<I: Id>(mut self, id: I) -> Self {
+        self.request.item_type = I::item_type();
+        self.request.id = Some(id.into());
+        self.request.secondary_item_type = Some(T::item_type());
+        self
+    }
+
+    /// Adds a sort to the request. It can be called several times,
+    /// the first sort being the primary key ( see [`RequestBuilder::sort`] ).
+    pub fn sort(mut self, sort: Sort<T::Attribute>) -> Self {
+        self.request.sorts.push(sort.into_attribute());
+        self
+    }
+
+    /// Adds a filter to the request. It can be called several times,
+    /// the request then returns the items that match all the filters.
+    pub fn filter(mut self, filter: Filter<T::Attribute>) -> Self {
+        self.request.filters.push(filter.into_attribute());
+        self
+    }
+
+    /// Builds the request. If the request is invalid, an error is returned.
+    ///
+    /// # Errors
+    ///
+    /// See [`RequestBuilder::build`].
+    pub fn build(self) -> Result<TypedRequest<T>, Error> {
+        Ok(TypedRequest {
+            request: self.request.validate()?,
+            _item: PhantomData,
+        })
     }
 }
 
+/// The routes with a secondary item type that the API supports, like `book/{id}/chapter`,
+/// along with the attribute of the secondary items that holds the id of the item.
+const SECONDARY_ITEM_TYPES: [(ItemType, ItemType, Attribute); 3] = [
+    (
+        ItemType::Book,
+        ItemType::Chapter,
+        Attribute::Chapter(ChapterAttribute::Book),
+    ),
+    (
+        ItemType::Movie,
+        ItemType::Quote,
+        Attribute::Quote(QuoteAttribute::Movie),
+    ),
+    (
+        ItemType::Character,
+        ItemType::Quote,
+        Attribute::Quote(QuoteAttribute::Character),
+    ),
+];
+
 /// This struct represents a request to the API.
-/// It should be created with the [`RequestBuilder`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// It should be created with the [`RequestBuilder`], or parsed from its url.
+///
+/// # Example
+/// ```
+/// use lotr_api::{Request, request::GetUrl};
+///
+/// let url = "character/5cd99d4bde30eff6ebccfbe6/quote?sort=dialog:asc&dialog=Deagol!&limit=10";
+/// let request: Request = url.parse().unwrap();
+///
+/// assert_eq!(request.get_url(), url);
+/// ```
+///
+/// The url of a request only holds the filters that are pushed down to the API
+/// ( see [`Plan`] ). [`Request::try_get_url`] returns an error for the requests with
+/// other filters, since their url can't be parsed back into them.
+///
+/// ```
+/// use lotr_api::{ItemType, Request, RequestBuilder,
+///     attribute::{Attribute, CharacterAttribute},
+///     filter::{Filter, Operator}};
+///
+/// let realm = |realm: &str| Filter::Match(
+///     Attribute::Character(CharacterAttribute::Realm),
+///     Operator::Eq,
+///     vec![realm.into()],
+/// );
+///
+/// // Pushed down as `realm=Gondor,Rohan`, and parsed back as such.
+/// let request = RequestBuilder::new(ItemType::Character)
+///     .filter(Filter::Or(vec![realm("Gondor"), realm("Rohan")]))
+///     .build()
+///     .unwrap();
+/// let url = request.try_get_url().unwrap();
+/// assert_eq!(url, "character?realm=Gondor,Rohan");
+///
+/// let request = RequestBuilder::new(ItemType::Character)
+///     .filter(Filter::Or(vec![
+///         realm("Gondor"),
+///         Filter::Exists(Attribute::Character(CharacterAttribute::Spouse), true),
+///     ]))
+///     .build()
+///     .unwrap();
+/// assert!(request.try_get_url().is_err());
+/// ```
+///
+/// Requests can also be serialized with serde, to be saved in a configuration file or sent
+/// elsewhere. The serialized request holds a `version` field ( see [`FORMAT_VERSION`] ),
+/// and it is validated again when it is deserialized.
+///
+/// ```
+/// use lotr_api::{attribute::{Attribute, BookAttribute}, sort::{Sort, SortOrder}, ItemType, Request, RequestBuilder};
+///
+/// let request = RequestBuilder::new(ItemType::Book)
+///     .sort(Sort::new(SortOrder::Ascending, Attribute::Book(BookAttribute::Name)))
+///     .build()
+///     .unwrap();
+///
+/// let json = serde_json::to_string(&request).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"version":4,"item_type":"book","sorts":[{"order":"asc","by":{"book":"name"}}]}"#
+/// );
+/// assert_eq!(serde_json::from_str::<Request>(&json).unwrap(), request);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(
+    try_from = "serialization::SerializedRequest",
+    into = "serialization::SerializedRequest"
+)]
 pub struct Request {
     item_type: ItemType,
     id: Option<String>,
     secondary_item_type: Option<ItemType>,
-    sort: Option<Sort>,
-    filter: Option<Filter>,
+    sorts: Vec<Sort>,
+    filters: Vec<Filter>,
     pagination: Option<Pagination>,
 }
 
@@ -228,12 +536,168 @@ impl Request {
             item_type,
             id: None,
             secondary_item_type: None,
-            sort: None,
-            filter: None,
+            sorts: Vec::new(),
+            filters: Vec::new(),
             pagination: None,
         }
     }
 
+    fn validate(self) -> Result<Self, Error> {
+        if let Some(id) = &self.id {
+            id::validate(id)?;
+        }
+        let item_type = self.get_item_type();
+        for sort in &self.sorts {
+            if sort.get_item_type() != item_type {
+                return Err(Error::InvalidSort);
+            }
+        }
+        for filter in &self.filters {
+            if !filter.is_for(&item_type) {
+                return Err(Error::InvalidFilter);
+            }
+            filter.validate()?;
+        }
+        if let Some(secondary_item_type) = &self.secondary_item_type {
+            if self.parent_attribute(secondary_item_type).is_none() {
+                return Err(Error::UnsupportedSecondaryItemType {
+                    item_type: self.item_type.clone(),
+                    secondary_item_type: secondary_item_type.clone(),
+                });
+            }
+            // Every secondary item type needs an id.
+            if self.id.is_none() {
+                return Err(Error::InvalidSecondaryItemType);
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the same request with the given pagination.
+    pub(crate) fn with_pagination(mut self, pagination: Pagination) -> Self {
+        self.pagination = Some(pagination);
+        self
+    }
+
+    /// Returns the attribute of the items of the secondary item type that holds the id
+    /// of the item, or `None` if the API has no route for them.
+    fn parent_attribute(&self, secondary_item_type: &ItemType) -> Option<Attribute> {
+        SECONDARY_ITEM_TYPES
+            .iter()
+            .find(|(item_type, secondary, _)| {
+                item_type == &self.item_type && secondary == secondary_item_type
+            })
+            .map(|(_, _, attribute)| *attribute)
+    }
+
+    /// Applies the request to items that are already in memory, like a dataset that was
+    /// fetched earlier, and returns the items the API would return for it.
+    ///
+    /// The id, the filters, the sorts and the pagination are evaluated with the same
+    /// semantics as the API: the values that are not set ( `None` ) don't exist, match
+    /// [`Operator::Ne`] and never match the comparisons like [`Operator::Gt`], and they
+    /// come first when sorting in ascending order. The items of another item type
+    /// are ignored.
+    ///
+    /// An attribute that the API returns as `null` is deserialized as `None` too, so it
+    /// doesn't exist either. The client evaluates the filters the API can't express
+    /// the same way ( see [`Plan`] ).
+    ///
+    /// # Example
+    /// ```
+    /// use lotr_api::{Book, Item, Request};
+    ///
+    /// let books: Vec<Item> = [
+    ///     ("5cf5805fb53e011a64671582", "The Fellowship Of The Ring"),
+    ///     ("5cf58077b53e011a64671583", "The Two Towers"),
+    ///     ("5cf58080b53e011a64671584", "The Return Of The King"),
+    /// ]
+    /// .into_iter()
+    /// .map(|(id, name)| {
+    ///     Item::Book(Book {
+    ///         _id: id.parse().unwrap(),
+    ///         name: name.to_string(),
+    ///         extra: Default::default(),
+    ///     })
+    /// })
+    /// .collect();
+    ///
+    /// let request: Request = "book?sort=name:asc&name!=The%20Two%20Towers".parse().unwrap();
+    /// let names: Vec<_> = request
+    ///     .apply(&books)
+    ///     .into_iter()
+    ///     .map(|item| match item {
+    ///         Item::Book(book) => book.name,
+    ///         _ => unreachable!(),
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(names, vec!["The Fellowship Of The Ring", "The Return Of The King"]);
+    /// ```
+    pub fn apply(&self, items: &[Item]) -> Vec<Item> {
+        let item_type = self.get_item_type();
+        self.select(
+            items
+                .iter()
+                .filter(|item| item.item_type() == item_type)
+                .map(|item| (item.to_json(), item)),
+        )
+    }
+
+    /// Returns the given items, with their JSON, that match the request, sorted and paginated.
+    fn select<'a, T: Clone + 'a>(&self, items: impl Iterator<Item = (Value, &'a T)>) -> Vec<T> {
+        let filters: Vec<_> = self
+            .path_filter()
+            .into_iter()
+            .chain(self.filters.clone())
+            .collect();
+        let mut items: Vec<_> = items
+            .filter(|(value, _)| filters.iter().all(|filter| filter.matches(value)))
+            .collect();
+        items.sort_by(|(a, _), (b, _)| sort::compare(&self.sorts, a, b));
+
+        let items: Vec<_> = items.into_iter().map(|(_, item)| item).collect();
+        Page::from_items(&items, self.clone())
+            .into_items()
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the filter that matches the items of the path of the request, like
+    /// the chapters of the book in `book/{id}/chapter`, if it has an id.
+    fn path_filter(&self) -> Option<Filter> {
+        let id = self.id.as_ref()?;
+        let attribute = match &self.secondary_item_type {
+            Some(secondary_item_type) => self.parent_attribute(secondary_item_type)?,
+            None => Attribute::id(&self.item_type),
+        };
+        Some(Filter::Match(
+            attribute,
+            Operator::Eq,
+            vec![FilterValue::id(id.clone())],
+        ))
+    }
+
+    /// Returns the plan of the request, which tells what is pushed down to the API
+    /// and what is evaluated by the client. See [`Plan`].
+    pub fn plan(&self) -> Plan {
+        Plan::new(self)
+    }
+
+    /// Returns the url of the request, or an [`Error::UnsupportedFilter`] if the API
+    /// can't express one of its filters. Unlike [`GetUrl::get_url`], which leaves
+    /// these filters out, the url holds the whole request: it is parsed back into
+    /// the same request, except for the combinations of filters, which are parsed
+    /// back as the filters the API receives.
+    pub fn try_get_url(&self) -> Result<String, Error> {
+        match self.plan().local_filters().first() {
+            Some(filter) => Err(Error::UnsupportedFilter(format!("{:?}", filter))),
+            None => Ok(self.get_url()),
+        }
+    }
+
     pub(crate) fn get_item_type(&self) -> ItemType {
         if let Some(secondary_item_type) = &self.secondary_item_type {
             secondary_item_type.clone()
@@ -247,20 +711,23 @@ impl GetUrl for Request {
     fn get_url(&self) -> String {
         let mut url = self.item_type.get_url();
         if let Some(id) = &self.id {
-            url.push_str(&format!("/{}", id));
+            url.push_str(&format!("/{}", encoding::encode(id)));
         }
         if let Some(secondary_item_type) = &self.secondary_item_type {
             url.push_str(&format!("/{}", secondary_item_type.get_url()));
         }
 
+        // The url holds every sort, so that it can be parsed back into the request,
+        // but only the filters pushed down to the API, see `Plan`.
+        let plan = self.plan();
         let mut aditional_url = vec![];
-        if let Some(sort) = &self.sort {
+        for sort in &self.sorts {
             aditional_url.push(sort.get_url());
         }
-        if let Some(filter) = &self.filter {
+        for filter in plan.remote_filters() {
             aditional_url.push(filter.get_url());
         }
-        if let Some(pagination) = &self.pagination {
+        if let Some(pagination) = self.pagination.as_ref().filter(|p| !p.is_empty()) {
             aditional_url.push(pagination.get_url());
         }
 
@@ -273,52 +740,182 @@ impl GetUrl for Request {
     }
 }
 
-/// Wrapper for the [`reqwest::Client`] struct that contains the token
-/// and the actual url that is used to make the request.
-/// It is used to make requests to the API.
+/// A [`Request`] that returns items of type `T`.
+/// It is created with [`RequestBuilder::typed`].
+pub struct TypedRequest<T> {
+    request: Request,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T: TypedItem> TypedRequest<T> {
+    /// Applies the request to items that are already in memory.
+    /// See [`Request::apply`].
+    pub fn apply(&self, items: &[T]) -> Vec<T> {
+        self.request.select(items.iter().map(|item| {
+            let json = serde_json::to_value(item).expect("the items serialize into JSON objects");
+            (json, item)
+        }))
+    }
+}
+
+impl<T> TypedRequest<T> {
+    /// Returns the underlying request.
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// Returns the underlying request, consuming the typed one.
+    pub fn into_request(self) -> Request {
+        self.request
+    }
+}
+
+impl<T> From<TypedRequest<T>> for Request {
+    fn from(request: TypedRequest<T>) -> Self {
+        request.request
+    }
+}
+
+impl<T> Clone for TypedRequest<T> {
+    fn clone(&self) -> Self {
+        Self {
+            request: self.request.clone(),
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TypedRequest<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_tuple("TypedRequest")
+            .field(&self.request)
+            .finish()
+    }
+}
+
+impl<T> PartialEq for TypedRequest<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.request == other.request
+    }
+}
+
+impl<T> Eq for TypedRequest<T> {}
+
+impl<T> GetUrl for TypedRequest<T> {
+    fn get_url(&self) -> String {
+        self.request.get_url()
+    }
+}
+
+/// Wrapper for the [`Transport`] that contains the base url of the API and the
+/// headers (token included) that are sent with every request.
+/// It is used to make requests to the API, taking care of the rate limit and the retries.
+///
+/// The transport is shared, so cloning the requester is cheap and every clone
+/// reuses the same connections. The rate limiter, if any, is shared by the clones too.
+#[derive(Debug, Clone)]
 pub(crate) struct Requester {
-    token: String,
+    transport: Arc<dyn Transport>,
+    base_url: String,
+    headers: HeaderMap,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    retry_policy: RetryPolicy,
 }
 
 impl Requester {
-    pub(crate) fn new(token: String) -> Self {
-        Self { token }
+    pub(crate) fn new(transport: Arc<dyn Transport>, base_url: String, headers: HeaderMap) -> Self {
+        Self {
+            transport,
+            base_url,
+            headers,
+            rate_limiter: None,
+            retry_policy: RetryPolicy::default(),
+        }
     }
 
-    pub(crate) async fn get(&self, url: &str) -> Result<String, reqwest::Error> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            header::ACCEPT,
-            HeaderValue::from_str("application/json")
-                .expect("Failed to convert header to header value"),
-        );
-        headers.insert(
-            header::AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.token))
-                .expect("Failed to convert header to header value"),
-        );
+    pub(crate) fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub(crate) fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
 
-        let client = reqwest::Client::new();
-        match client
-            .get(format!("https://the-one-api.dev/v2/{}", url))
-            .headers(headers)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let response = response.error_for_status()?;
-                response.text().await
+    /// Returns the number of requests that can be made before the rate limiter
+    /// kicks in, or `None` if there is no rate limiter.
+    pub(crate) fn remaining_requests(&self) -> Option<u32> {
+        self.rate_limiter
+            .as_ref()
+            .map(|rate_limiter| rate_limiter.remaining())
+    }
+
+    /// Makes a request to the given url ( relative to the base url ) and returns the body
+    /// of the response. Failed requests are retried according to the retry policy.
+    pub(crate) async fn get(&self, url: &str) -> Result<String, Error> {
+        let mut attempt = 1;
+        loop {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await?;
             }
-            Err(e) => Err(e),
+            let can_retry = attempt < self.retry_policy.max_attempts();
+            let response = self
+                .transport
+                .get(TransportRequest {
+                    url: format!("{}{}", self.base_url, url),
+                    path: url.to_string(),
+                    headers: self.headers.clone(),
+                })
+                .await;
+
+            let delay = match response {
+                Ok(response) => {
+                    let status = response.status;
+                    let delay = if can_retry && self.retry_policy.retries_status(status) {
+                        self.retry_policy
+                            .delay_for_response(attempt, status, &response.headers)
+                    } else {
+                        None
+                    };
+                    match delay {
+                        Some(delay) => delay,
+                        None if status.is_success() => return Ok(response.body),
+                        None => {
+                            let retry_after = self::retry::server_delay(status, &response.headers);
+                            return Err(Error::from_status(status, retry_after, response.body));
+                        }
+                    }
+                }
+                Err(error) if can_retry && self.retry_policy.retries_error(&error) => {
+                    self.retry_policy.backoff(attempt)
+                }
+                Err(error) => return Err(error),
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
     }
 
-    pub(crate) async fn get_from_request(
-        &self,
-        request: Request,
-    ) -> Result<String, reqwest::Error> {
-        let url = request.get_url();
-        self.get(&url).await
+    /// Makes a request to the given url and deserializes the response.
+    pub(crate) async fn get_json<T>(&self, url: &str) -> Result<T, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let body = self.get(url).await?;
+        serde_json::from_str(&body)
+            .map_err(|error| Error::decode(&format!("{}{}", self.base_url, url), &body, error))
+    }
+
+    pub(crate) async fn get_from_request<T>(&self, request: Request) -> Result<T, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        // Only the part of the request that the API supports is sent, see `Plan`.
+        let url = request.plan().remote_request().get_url();
+        self.get_json(&url).await
     }
 }
 
@@ -326,13 +923,20 @@ impl Requester {
 mod tests {
 
     use crate::{
-        attribute::{Attribute, BookAttribute, QuoteAttribute},
+        attribute::{Attribute, BookAttribute, MovieAttribute, QuoteAttribute},
         filter::Operator,
         request::sort::SortOrder,
+        BookId, CharacterId, MovieId, Quote, QuoteId,
     };
 
     use super::*;
 
+    const CHARACTER_ID: &str = "5cd99d4bde30eff6ebccfbe6";
+
+    fn character_id() -> CharacterId {
+        CHARACTER_ID.parse().unwrap()
+    }
+
     #[test]
     fn test_simple_request_url() {
         let request = RequestBuilder::new(ItemType::Book).build().unwrap();
@@ -341,11 +945,23 @@ mod tests {
 
     #[test]
     fn test_request_with_id_url() {
-        let request = RequestBuilder::new(ItemType::Book)
-            .id("123".to_string())
-            .build()
-            .unwrap();
-        assert_eq!(request.get_url(), "book/123");
+        let id: BookId = "5cf5805fb53e011a64671582".parse().unwrap();
+        let request = RequestBuilder::new(ItemType::Book).id(id).build().unwrap();
+        assert_eq!(request.get_url(), "book/5cf5805fb53e011a64671582");
+    }
+
+    #[test]
+    fn test_request_with_mismatched_id() {
+        let request = RequestBuilder::new(ItemType::Movie)
+            .id(character_id())
+            .build();
+        assert!(matches!(
+            request,
+            Err(Error::MismatchedId {
+                expected: ItemType::Movie,
+                found: ItemType::Character,
+            })
+        ));
     }
 
     #[test]
@@ -356,12 +972,55 @@ mod tests {
         assert!(request.is_err());
 
         let request = RequestBuilder::new(ItemType::Character)
-            .id("123".to_string())
+            .id(character_id())
             .secondary_item_type(ItemType::Quote)
             .build()
             .unwrap();
 
-        assert_eq!(request.get_url(), "character/123/quote");
+        assert_eq!(
+            request.get_url(),
+            "character/5cd99d4bde30eff6ebccfbe6/quote"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_secondary_item_type() {
+        let supported = [
+            (ItemType::Book, ItemType::Chapter),
+            (ItemType::Movie, ItemType::Quote),
+            (ItemType::Character, ItemType::Quote),
+        ];
+        let item_types = [
+            ItemType::Book,
+            ItemType::Movie,
+            ItemType::Quote,
+            ItemType::Character,
+            ItemType::Chapter,
+        ];
+
+        for item_type in &item_types {
+            for secondary_item_type in &item_types {
+                let request = format!("{}/{}/{}", item_type, CHARACTER_ID, secondary_item_type)
+                    .parse::<Request>();
+                if supported.contains(&(item_type.clone(), secondary_item_type.clone())) {
+                    assert!(request.is_ok());
+                } else {
+                    assert!(matches!(
+                        request,
+                        Err(Error::UnsupportedSecondaryItemType { item_type: ref i, secondary_item_type: ref s })
+                            if i == item_type && s == secondary_item_type
+                    ));
+                }
+            }
+        }
+
+        let request = RequestBuilder::<Quote>::typed()
+            .parent(BookId::new("5cf5805fb53e011a64671582").unwrap())
+            .build();
+        assert!(matches!(
+            request,
+            Err(Error::UnsupportedSecondaryItemType { .. })
+        ));
     }
 
     #[test]
@@ -377,18 +1036,73 @@ mod tests {
         assert_eq!(request.get_url(), "book?sort=name:asc");
     }
 
+    #[test]
+    fn test_request_with_multiple_sorts() {
+        let request = RequestBuilder::new(ItemType::Movie)
+            .sort(Sort::new(
+                SortOrder::Descending,
+                Attribute::Movie(MovieAttribute::AcademyAwardWins),
+            ))
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Movie(MovieAttribute::Name),
+            ))
+            .pagination(Pagination::new(10, 0, 2))
+            .build()
+            .unwrap();
+
+        assert!(!request.plan().is_remote());
+        assert_eq!(
+            request.get_url(),
+            "movie?sort=academyAwardWins:desc&sort=name:asc&limit=10&page=2"
+        );
+        assert_eq!(
+            request.plan().remote_request().get_url(),
+            "movie?sort=academyAwardWins:desc"
+        );
+
+        let request = RequestBuilder::new(ItemType::Movie)
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Movie(MovieAttribute::Name),
+            ))
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Book(BookAttribute::Name),
+            ))
+            .build();
+        assert!(matches!(request, Err(Error::InvalidSort)));
+    }
+
     #[test]
     fn test_request_with_filter_url() {
         let request = RequestBuilder::new(ItemType::Book)
             .filter(Filter::Match(
                 Attribute::Book(BookAttribute::Name),
                 Operator::Eq,
-                vec!["The Fellowship of the Ring".to_string()],
+                vec!["The Fellowship of the Ring".into()],
             ))
             .build()
             .unwrap();
 
-        assert_eq!(request.get_url(), "book?name=The Fellowship of the Ring");
+        assert_eq!(
+            request.get_url(),
+            "book?name=The%20Fellowship%20of%20the%20Ring"
+        );
+    }
+
+    #[test]
+    fn test_request_with_invalid_id() {
+        let request = "book/12%2F3%3Fa%3Db".parse::<Request>();
+        assert!(matches!(request, Err(Error::InvalidId(ref id)) if id == "12/3?a=b"));
+    }
+
+    #[test]
+    fn test_request_with_encoded_id_url() {
+        let request = "book/5cf5805fb53e011a6467158%32"
+            .parse::<Request>()
+            .unwrap();
+        assert_eq!(request.get_url(), "book/5cf5805fb53e011a64671582");
     }
 
     #[test]
@@ -401,10 +1115,70 @@ mod tests {
         assert_eq!(request.get_url(), "book?limit=10&offset=10&page=2");
     }
 
+    #[test]
+    fn test_request_with_empty_pagination() {
+        let request = RequestBuilder::new(ItemType::Book)
+            .pagination(Pagination::new(0, 0, 0))
+            .build()
+            .unwrap();
+
+        assert_eq!(request, Request::new(ItemType::Book));
+        assert_eq!(request.get_url(), "book");
+    }
+
+    #[test]
+    fn test_request_with_empty_filter() {
+        let request = RequestBuilder::new(ItemType::Book)
+            .filter(Filter::Match(
+                Attribute::Book(BookAttribute::Name),
+                Operator::Eq,
+                vec![],
+            ))
+            .build();
+
+        assert!(request.is_err());
+    }
+
     #[test]
     fn test_full_request_url() {
         let request = RequestBuilder::new(ItemType::Character)
-            .id("123".to_string())
+            .id(character_id())
+            .secondary_item_type(ItemType::Quote)
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Quote(QuoteAttribute::Dialog),
+            ))
+            .filter(Filter::Match(
+                Attribute::Quote(QuoteAttribute::Dialog),
+                Operator::Eq,
+                vec!["Deagol!".into()],
+            ))
+            .pagination(Pagination::new(10, 10, 2))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.get_url(),
+            "character/5cd99d4bde30eff6ebccfbe6/quote?sort=dialog:asc&dialog=Deagol!&limit=10&offset=10&page=2"
+        );
+    }
+
+    #[test]
+    fn test_typed_request_url() {
+        let request = RequestBuilder::<Quote>::typed()
+            .parent(character_id())
+            .sort(Sort::new(SortOrder::Ascending, QuoteAttribute::Dialog))
+            .filter(Filter::Match(
+                QuoteAttribute::Dialog,
+                Operator::Eq,
+                vec!["Deagol!".into()],
+            ))
+            .pagination(Pagination::new(10, 10, 2))
+            .build()
+            .unwrap();
+
+        let untyped = RequestBuilder::new(ItemType::Character)
+            .id(character_id())
             .secondary_item_type(ItemType::Quote)
             .sort(Sort::new(
                 SortOrder::Ascending,
@@ -413,15 +1187,123 @@ mod tests {
             .filter(Filter::Match(
                 Attribute::Quote(QuoteAttribute::Dialog),
                 Operator::Eq,
-                vec!["Deagol!".to_string()],
+                vec!["Deagol!".into()],
             ))
             .pagination(Pagination::new(10, 10, 2))
             .build()
             .unwrap();
 
+        assert_eq!(request.request(), &untyped);
+        assert_eq!(request.get_url(), untyped.get_url());
+    }
+
+    #[test]
+    fn test_typed_request_id_replaces_parent() {
+        let id: QuoteId = "5cd96e05de30eff6ebcce7e9".parse().unwrap();
+        let request = RequestBuilder::<Quote>::typed()
+            .parent(character_id())
+            .id(id.clone())
+            .build()
+            .unwrap();
+        assert_eq!(request.get_url(), format!("quote/{}", id));
+
+        let request = RequestBuilder::<Quote>::typed()
+            .id(id)
+            .parent(character_id())
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.get_url(),
+            format!("character/{}/quote", character_id())
+        );
+    }
+
+    #[test]
+    fn test_request_with_multiple_filters_url() {
+        let request = RequestBuilder::new(ItemType::Movie)
+            .filter(Filter::Match(
+                Attribute::Movie(MovieAttribute::BudgetInMillions),
+                Operator::Gte,
+                vec![100.into()],
+            ))
+            .filter(Filter::Match(
+                Attribute::Movie(MovieAttribute::AcademyAwardWins),
+                Operator::Gt,
+                vec![0.into()],
+            ))
+            .filter(Filter::Exists(
+                Attribute::Movie(MovieAttribute::RottenTomatoesScore),
+                true,
+            ))
+            .build()
+            .unwrap();
+
         assert_eq!(
             request.get_url(),
-            "character/123/quote?sort=dialog:asc&dialog=Deagol!&limit=10&offset=10&page=2"
+            "movie?budgetInMillions>=100&academyAwardWins>0&rottenTomatoesScore"
+        );
+
+        let request = RequestBuilder::new(ItemType::Movie)
+            .filter(Filter::Exists(Attribute::Movie(MovieAttribute::Name), true))
+            .filter(Filter::Exists(Attribute::Book(BookAttribute::Name), true))
+            .build();
+        assert!(request.is_err());
+    }
+
+    #[test]
+    fn test_apply_path() {
+        // The ids end with the given digit.
+        let id = |digit: char| format!("{:0>24}", digit);
+        let quote = |digit: char, movie: char, character: char| {
+            Item::Quote(Quote {
+                _id: id(digit).parse().unwrap(),
+                dialog: None,
+                movie: MovieId::new(id(movie)).unwrap(),
+                character: CharacterId::new(id(character)).unwrap(),
+                id: id(digit).parse().unwrap(),
+                extra: Default::default(),
+            })
+        };
+        let items = vec![
+            quote('1', 'a', 'e'),
+            quote('2', 'b', 'e'),
+            quote('3', 'a', 'f'),
+        ];
+
+        let ids = |url: &str| {
+            let request: Request = url.parse().unwrap();
+            request
+                .apply(&items)
+                .into_iter()
+                .map(|item| match item {
+                    Item::Quote(quote) => quote._id.to_string(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(&format!("movie/{}/quote", id('a'))),
+            vec![id('1'), id('3')]
         );
+        assert_eq!(
+            ids(&format!("character/{}/quote?sort=_id:desc", id('e'))),
+            vec![id('2'), id('1')]
+        );
+        assert_eq!(ids(&format!("quote/{}", id('2'))), vec![id('2')]);
+        assert!(ids(&format!("book/{}", id('a'))).is_empty());
+    }
+
+    #[test]
+    fn test_request_with_invalid_regex() {
+        let request = RequestBuilder::new(ItemType::Book)
+            .filter(Filter::Regex(
+                Attribute::Book(BookAttribute::Name),
+                Operator::Eq,
+                "[Ring".to_string(),
+                "i".to_string(),
+            ))
+            .build();
+
+        assert!(matches!(request, Err(Error::InvalidRegex { .. })));
     }
 }
@@ -0,0 +1,309 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{item::object::Response, Error, Pagination};
+
+use super::{
+    decode::{self, DecodeMode, SkippedDocument},
+    Request,
+};
+
+/// A page of results of a [`Request`], along with the pagination data returned by the API.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use lotr_api::{Client, ItemType, Pagination, RequestBuilder};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = Client::new("your_token".to_string());
+///     let request = RequestBuilder::new(ItemType::Quote)
+///         .pagination(Pagination::new(100, 0, 1))
+///         .build()
+///         .unwrap();
+///
+///     let mut page = client.get_page(request).await.unwrap();
+///     println!("{} quotes in {:?} pages", page.total(), page.pages());
+///     while let Some(request) = page.next_request() {
+///         page = client.get_page(request).await.unwrap();
+///         // ...
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    items: Vec<T>,
+    total: u32,
+    limit: u32,
+    offset: u32,
+    page: Option<u32>,
+    pages: Option<u32>,
+    request: Request,
+    skipped: Vec<SkippedDocument>,
+}
+
+impl<T> Page<T> {
+    pub(crate) fn new(response: Response<T>, request: Request) -> Self {
+        Self {
+            items: response.docs,
+            total: response.total,
+            limit: response.limit,
+            offset: response.offset,
+            page: response.page,
+            pages: response.pages,
+            request,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Creates the page of the request out of all the items that match it, applying
+    /// its pagination like the API does. It is used when the client has to sort the items.
+    pub(crate) fn from_items(items: &[T], request: Request) -> Self
+    where
+        T: Clone,
+    {
+        let total = items.len() as u32;
+        let pagination = request.pagination.unwrap_or_default();
+        // Without a limit every item fits in a single page.
+        let limit = if pagination.limit == 0 {
+            total
+        } else {
+            pagination.limit
+        };
+        // The page is ignored if there is an offset.
+        let start = if pagination.offset != 0 {
+            pagination.offset
+        } else {
+            pagination
+                .page
+                .max(1)
+                .saturating_sub(1)
+                .saturating_mul(limit)
+        };
+        let page = start.checked_div(limit).unwrap_or(0) + 1;
+        let pages = if limit == 0 {
+            1
+        } else {
+            total.div_ceil(limit).max(1)
+        };
+
+        let docs = items
+            .iter()
+            .skip(start as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        Self::new(
+            Response {
+                docs,
+                total,
+                limit,
+                offset: pagination.offset,
+                page: Some(page),
+                pages: Some(pages),
+            },
+            request,
+        )
+    }
+
+    /// Returns the items of the page.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns the items of the page, consuming it.
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Returns the total number of items that match the request, in all pages.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    /// Returns the maximum number of items per page.
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Returns the number of items that were skipped before this page.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Returns the number of this page, starting at 1.
+    pub fn page(&self) -> Option<u32> {
+        self.page
+    }
+
+    /// Returns the total number of pages.
+    pub fn pages(&self) -> Option<u32> {
+        self.pages
+    }
+
+    /// Returns the documents of the page that couldn't be deserialized, and were skipped
+    /// because the client uses [`DecodeMode::Lenient`]. They are not in [`Page::items`].
+    pub fn skipped(&self) -> &[SkippedDocument] {
+        &self.skipped
+    }
+
+    /// Returns the request that retrieved this page.
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
+    /// Returns `true` if there is a page after this one.
+    pub fn has_next(&self) -> bool {
+        self.next_pagination().is_some()
+    }
+
+    /// Returns the request that retrieves the page that follows this one,
+    /// or `None` if this is the last page.
+    pub fn next_request(&self) -> Option<Request> {
+        self.next_pagination()
+            .map(|pagination| self.request.clone().with_pagination(pagination))
+    }
+
+    /// Applies the given function to the items of the page.
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+            page: self.page,
+            pages: self.pages,
+            request: self.request,
+            skipped: self.skipped,
+        }
+    }
+
+    /// If the request used an offset the API ignores the page, so the offset
+    /// is moved forward instead.
+    fn next_pagination(&self) -> Option<Pagination> {
+        if self.items.is_empty() {
+            return None;
+        }
+        if self.offset != 0 {
+            let offset = self.offset + self.limit;
+            return (offset < self.total).then(|| Pagination::new(self.limit, offset, 0));
+        }
+        match (self.page, self.pages) {
+            (Some(page), Some(pages)) if page < pages => {
+                Some(Pagination::new(self.limit, 0, page + 1))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Page<Value> {
+    /// Deserializes the documents of the page, returned by the API for the given url.
+    /// In [`DecodeMode::Lenient`] the documents that can't be deserialized are skipped.
+    pub(crate) fn decode<T: DeserializeOwned>(
+        self,
+        mode: DecodeMode,
+        url: &str,
+    ) -> Result<Page<T>, Error> {
+        let (items, skipped) = decode::decode(self.items, mode, url)?;
+        Ok(Page {
+            items,
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+            page: self.page,
+            pages: self.pages,
+            request: self.request,
+            skipped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{request::GetUrl, ItemType};
+
+    use super::*;
+
+    fn page(items: usize, total: u32, limit: u32, offset: u32, page: u32) -> Page<u32> {
+        Page::new(
+            Response {
+                docs: vec![0; items],
+                total,
+                limit,
+                offset,
+                page: Some(page),
+                pages: Some(total.div_ceil(limit)),
+            },
+            Request::new(ItemType::Quote),
+        )
+    }
+
+    #[test]
+    fn test_next_request() {
+        let first = page(1000, 2383, 1000, 0, 1);
+        assert!(first.has_next());
+        assert_eq!(
+            first.next_request().unwrap().get_url(),
+            "quote?limit=1000&page=2"
+        );
+
+        let last = page(383, 2383, 1000, 0, 3);
+        assert!(!last.has_next());
+        assert_eq!(last.next_request(), None);
+
+        assert_eq!(page(0, 0, 1000, 0, 1).next_request(), None);
+    }
+
+    #[test]
+    fn test_next_request_with_offset() {
+        let first = page(10, 25, 10, 5, 1);
+        assert_eq!(
+            first.next_request().unwrap().get_url(),
+            "quote?limit=10&offset=15"
+        );
+        assert!(!page(10, 25, 10, 15, 2).has_next());
+    }
+
+    #[test]
+    fn test_from_items() {
+        let request = |pagination| Request::new(ItemType::Quote).with_pagination(pagination);
+
+        let page = Page::from_items(&(0..5).collect::<Vec<_>>(), Request::new(ItemType::Quote));
+        assert_eq!(page.items(), &[0, 1, 2, 3, 4]);
+        assert_eq!(
+            (page.limit(), page.page(), page.pages()),
+            (5, Some(1), Some(1))
+        );
+        assert!(!page.has_next());
+
+        let page = Page::from_items(
+            &(0..5).collect::<Vec<_>>(),
+            request(Pagination::new(2, 0, 3)),
+        );
+        assert_eq!(page.items(), &[4]);
+        assert_eq!(
+            (page.total(), page.page(), page.pages()),
+            (5, Some(3), Some(3))
+        );
+        assert!(!page.has_next());
+
+        let page = Page::from_items(
+            &(0..5).collect::<Vec<_>>(),
+            request(Pagination::new(2, 1, 0)),
+        );
+        assert_eq!(page.items(), &[1, 2]);
+        assert_eq!(
+            page.next_request().unwrap().get_url(),
+            "quote?limit=2&offset=3"
+        );
+
+        let page = Page::<u32>::from_items(&[], Request::new(ItemType::Quote));
+        assert!(page.items().is_empty());
+        assert!(!page.has_next());
+    }
+}
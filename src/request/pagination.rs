@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 /// This struct contains the date for the pagination of the API.
 ///
 /// # Example
@@ -9,11 +11,12 @@
 ///
 /// assert_eq!(pagination.get_url(), "limit=10&offset=2&page=1");
 ///
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Pagination {
-    limit: u32,
-    offset: u32,
-    page: u32,
+    pub(crate) limit: u32,
+    pub(crate) offset: u32,
+    pub(crate) page: u32,
 }
 
 impl Pagination {
@@ -25,6 +28,11 @@ impl Pagination {
         }
     }
 
+    /// Returns `true` if none of the values is set.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.limit == 0 && self.offset == 0 && self.page == 0
+    }
+
     pub fn get_url(&self) -> String {
         let mut values = vec![];
 
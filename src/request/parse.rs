@@ -0,0 +1,407 @@
+//! Parsing of a [`Request`] back from its url.
+
+use std::str::FromStr;
+
+use percent_encoding::percent_decode_str;
+
+use crate::{attribute::Attribute, client::DEFAULT_BASE_URL, Error, ItemType};
+
+use super::{
+    filter::{Filter, FilterValue, Operator},
+    pagination::Pagination,
+    sort::{Sort, SortOrder},
+    Request,
+};
+
+/// The operators in the order they have to be tried, so that `>=` is not read as `>`.
+const OPERATORS: [(&str, Operator); 6] = [
+    (">=", Operator::Gte),
+    ("<=", Operator::Lte),
+    ("!=", Operator::Ne),
+    ("=", Operator::Eq),
+    (">", Operator::Gt),
+    ("<", Operator::Lt),
+];
+
+impl FromStr for Request {
+    type Err = Error;
+
+    /// Parses a request from the url returned by [`Request::try_get_url`]
+    /// ( or [`super::GetUrl::get_url`] ).
+    /// The url can also start with the [`DEFAULT_BASE_URL`].
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        Parser { url }.parse()
+    }
+}
+
+impl TryFrom<&str> for Request {
+    type Error = Error;
+
+    fn try_from(url: &str) -> Result<Self, Self::Error> {
+        url.parse()
+    }
+}
+
+struct Parser<'a> {
+    url: &'a str,
+}
+
+impl Parser<'_> {
+    fn parse(&self) -> Result<Request, Error> {
+        let url = self.url.strip_prefix(DEFAULT_BASE_URL).unwrap_or(self.url);
+        let url = url.trim_start_matches('/');
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+        let mut request = self.parse_path(path)?;
+        let item_type = request.get_item_type();
+
+        let (mut limit, mut offset, mut page) = (0, 0, 0);
+        for part in query.split('&').filter(|part| !part.is_empty()) {
+            match part.split_once('=') {
+                Some(("sort", value)) => request.sorts.push(self.parse_sort(&item_type, value)?),
+                Some(("limit", value)) => limit = self.parse_number(value)?,
+                Some(("offset", value)) => offset = self.parse_number(value)?,
+                Some(("page", value)) => page = self.parse_number(value)?,
+                _ => request.filters.push(self.parse_filter(&item_type, part)?),
+            }
+        }
+        let pagination = Pagination::new(limit, offset, page);
+        request.pagination = (!pagination.is_empty()).then_some(pagination);
+
+        request.validate()
+    }
+
+    fn parse_path(&self, path: &str) -> Result<Request, Error> {
+        let mut segments = path.split('/');
+        let item_type = match segments.next() {
+            Some(item_type) if !item_type.is_empty() => ItemType::from_str(item_type)?,
+            _ => return Err(self.error("the item type is missing")),
+        };
+
+        let mut request = Request::new(item_type);
+        if let Some(id) = segments.next() {
+            if id.is_empty() {
+                return Err(self.error("the id is empty"));
+            }
+            request.id = Some(self.decode(id)?);
+        }
+        if let Some(secondary_item_type) = segments.next() {
+            request.secondary_item_type = Some(ItemType::from_str(secondary_item_type)?);
+        }
+        if segments.next().is_some() {
+            return Err(self.error("the path has too many segments"));
+        }
+
+        Ok(request)
+    }
+
+    fn parse_sort(&self, item_type: &ItemType, value: &str) -> Result<Sort, Error> {
+        let (name, order) = value
+            .split_once(':')
+            .ok_or_else(|| self.error("the sort has no order"))?;
+        let order = match order {
+            "asc" => SortOrder::Ascending,
+            "desc" => SortOrder::Descending,
+            _ => return Err(self.error(&format!("unknown sort order {}", order))),
+        };
+
+        Ok(Sort::new(order, self.parse_attribute(item_type, name)?))
+    }
+
+    fn parse_filter(&self, item_type: &ItemType, part: &str) -> Result<Filter, Error> {
+        if let Some(name) = part.strip_prefix('!') {
+            if !name.contains(['=', '<', '>']) {
+                return Ok(Filter::Exists(
+                    self.parse_attribute(item_type, name)?,
+                    false,
+                ));
+            }
+        }
+
+        let name_length = part
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(part.len());
+        let (name, rest) = part.split_at(name_length);
+        let attribute = self.parse_attribute(item_type, name)?;
+        if rest.is_empty() {
+            return Ok(Filter::Exists(attribute, true));
+        }
+
+        let (operator, value) = OPERATORS
+            .iter()
+            .find_map(|(symbol, operator)| rest.strip_prefix(symbol).map(|v| (*operator, v)))
+            .ok_or_else(|| self.error(&format!("the filter {} has no operator", part)))?;
+
+        if let Some((pattern, flags)) = value
+            .strip_prefix('/')
+            .and_then(|value| value.rsplit_once('/'))
+        {
+            return Ok(Filter::Regex(
+                attribute,
+                operator,
+                self.decode(pattern)?,
+                self.decode(flags)?,
+            ));
+        }
+
+        let kind = attribute.value_kind();
+        let values = value
+            .split(',')
+            .map(|value| Ok(FilterValue::from_url(kind, self.decode(value)?)))
+            .collect::<Result<_, Error>>()?;
+        Ok(Filter::Match(attribute, operator, values))
+    }
+
+    fn parse_attribute(&self, item_type: &ItemType, name: &str) -> Result<Attribute, Error> {
+        Attribute::from_url(item_type, name).ok_or_else(|| Error::UnknownAttribute {
+            item_type: item_type.clone(),
+            name: name.to_string(),
+        })
+    }
+
+    fn parse_number(&self, value: &str) -> Result<u32, Error> {
+        value
+            .parse()
+            .map_err(|_| self.error(&format!("{} is not a valid number", value)))
+    }
+
+    fn decode(&self, value: &str) -> Result<String, Error> {
+        percent_decode_str(value)
+            .decode_utf8()
+            .map(String::from)
+            .map_err(|_| self.error(&format!("{} is not valid utf-8", value)))
+    }
+
+    fn error(&self, reason: &str) -> Error {
+        Error::InvalidUrl {
+            url: self.url.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        attribute::{BookAttribute, CharacterAttribute, MovieAttribute, QuoteAttribute},
+        request::{GetUrl, RequestBuilder},
+        BookId, CharacterId,
+    };
+
+    use super::*;
+
+    fn assert_round_trip(request: Request) {
+        let url = request.try_get_url().unwrap();
+        assert_eq!(url.parse::<Request>().unwrap(), request, "{}", url);
+    }
+
+    #[test]
+    fn test_parse() {
+        let request: Request =
+            "character/5cd99d4bde30eff6ebccfbe6/quote?sort=dialog:asc&dialog=Deagol!&limit=10"
+                .parse()
+                .unwrap();
+        let expected = RequestBuilder::new(ItemType::Character)
+            .id(CharacterId::new("5cd99d4bde30eff6ebccfbe6").unwrap())
+            .secondary_item_type(ItemType::Quote)
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Quote(QuoteAttribute::Dialog),
+            ))
+            .filter(Filter::Match(
+                Attribute::Quote(QuoteAttribute::Dialog),
+                Operator::Eq,
+                vec!["Deagol!".into()],
+            ))
+            .pagination(Pagination::new(10, 0, 0))
+            .build()
+            .unwrap();
+
+        assert_eq!(request, expected);
+        assert_eq!(
+            Request::try_from(expected.get_url().as_str()).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_base_url() {
+        let request: Request = "https://the-one-api.dev/v2/book?name=The%20Two%20Towers"
+            .parse()
+            .unwrap();
+        assert_eq!(request.get_url(), "book?name=The%20Two%20Towers");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let requests = vec![
+            RequestBuilder::new(ItemType::Book).build().unwrap(),
+            RequestBuilder::new(ItemType::Book)
+                .id(BookId::new("5cf5805fb53e011a64671582").unwrap())
+                .secondary_item_type(ItemType::Chapter)
+                .build()
+                .unwrap(),
+            RequestBuilder::new(ItemType::Character)
+                .filter(Filter::Exists(
+                    Attribute::Character(CharacterAttribute::Spouse),
+                    false,
+                ))
+                .filter(Filter::Exists(
+                    Attribute::Character(CharacterAttribute::Hair),
+                    true,
+                ))
+                .filter(Filter::Match(
+                    Attribute::Character(CharacterAttribute::Name),
+                    Operator::Ne,
+                    vec!["Éowyn".into(), "a,b&c=d".into(), "/x/".into()],
+                ))
+                .filter(Filter::Regex(
+                    Attribute::Character(CharacterAttribute::Name),
+                    Operator::Ne,
+                    "^Gan/d.*f$".to_string(),
+                    "i".to_string(),
+                ))
+                .pagination(Pagination::new(0, 5, 0))
+                .build()
+                .unwrap(),
+            RequestBuilder::new(ItemType::Movie)
+                .sort(Sort::new(
+                    SortOrder::Descending,
+                    Attribute::Movie(MovieAttribute::BudgetInMillions),
+                ))
+                .filter(Filter::Match(
+                    Attribute::Movie(MovieAttribute::AcademyAwardWins),
+                    Operator::Gte,
+                    vec![1.into()],
+                ))
+                .filter(Filter::Match(
+                    Attribute::Movie(MovieAttribute::RuntimeInMinutes),
+                    Operator::Lt,
+                    vec![200.into()],
+                ))
+                .pagination(Pagination::new(10, 0, 3))
+                .build()
+                .unwrap(),
+            RequestBuilder::new(ItemType::Character)
+                .sort(Sort::new(
+                    SortOrder::Ascending,
+                    Attribute::Character(CharacterAttribute::Realm),
+                ))
+                .sort(Sort::new(
+                    SortOrder::Descending,
+                    Attribute::Character(CharacterAttribute::Name),
+                ))
+                .pagination(Pagination::new(10, 0, 2))
+                .build()
+                .unwrap(),
+            RequestBuilder::new(ItemType::Book)
+                .filter(Filter::Match(
+                    Attribute::Book(BookAttribute::Id),
+                    Operator::Eq,
+                    vec![FilterValue::id("5cf5805fb53e011a64671582")],
+                ))
+                .build()
+                .unwrap(),
+        ];
+
+        for request in requests {
+            assert_round_trip(request);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_combinators() {
+        let realm = |realm: &str| {
+            Filter::Match(
+                Attribute::Character(CharacterAttribute::Realm),
+                Operator::Eq,
+                vec![realm.into()],
+            )
+        };
+        let spouse = Filter::Exists(Attribute::Character(CharacterAttribute::Spouse), true);
+
+        // The combinations pushed down to the API are parsed back as the filters it receives.
+        let request = RequestBuilder::new(ItemType::Character)
+            .filter(Filter::Or(vec![realm("Gondor"), realm("Rohan")]))
+            .filter(Filter::Not(Box::new(spouse.clone())))
+            .build()
+            .unwrap();
+        let expected = RequestBuilder::new(ItemType::Character)
+            .filter(Filter::Match(
+                Attribute::Character(CharacterAttribute::Realm),
+                Operator::Eq,
+                vec!["Gondor".into(), "Rohan".into()],
+            ))
+            .filter(Filter::Exists(
+                Attribute::Character(CharacterAttribute::Spouse),
+                false,
+            ))
+            .build()
+            .unwrap();
+        let url = request.try_get_url().unwrap();
+        assert_eq!(url, "character?realm=Gondor,Rohan&!spouse");
+        assert_eq!(url.parse::<Request>().unwrap(), expected);
+        assert_round_trip(expected);
+
+        // The others have no url.
+        let request = RequestBuilder::new(ItemType::Character)
+            .filter(realm("Rohan"))
+            .filter(Filter::Or(vec![realm("Gondor"), spouse]))
+            .build()
+            .unwrap();
+        assert!(matches!(
+            request.try_get_url(),
+            Err(Error::UnsupportedFilter(_))
+        ));
+        assert_eq!(request.get_url(), "character?realm=Rohan");
+    }
+
+    #[test]
+    fn test_unknown_attribute() {
+        let error = "book?dialog=Deagol".parse::<Request>().unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UnknownAttribute { item_type: ItemType::Book, ref name } if name == "dialog"
+        ));
+
+        let error = "character/5cd99d4bde30eff6ebccfbe6/quote?sort=race:asc"
+            .parse::<Request>()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UnknownAttribute { item_type: ItemType::Quote, ref name } if name == "race"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_urls() {
+        assert!(matches!(
+            "dragon".parse::<Request>(),
+            Err(Error::UnknownItemType(name)) if name == "dragon"
+        ));
+        for url in [
+            "",
+            "book/1/chapter/2",
+            "book//chapter",
+            "book?sort=name",
+            "book?sort=name:up",
+            "book?limit=ten",
+            "book?name~Gandalf",
+        ] {
+            assert!(
+                matches!(url.parse::<Request>(), Err(Error::InvalidUrl { .. })),
+                "{}",
+                url
+            );
+        }
+        // The request is still validated.
+        assert!(matches!(
+            "movie?budgetInMillions>abc".parse::<Request>(),
+            Err(Error::InvalidFilterValue { .. })
+        ));
+        assert!(matches!(
+            "book?name>/Ring/".parse::<Request>(),
+            Err(Error::InvalidRegex { .. })
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! Planning of the requests.
+//!
+//! The API only supports a list of filters that must all match and a single sort.
+//! A [`Plan`] splits a [`Request`] into the part that is sent to the API and the part
+//! that the [`crate::Client`] applies itself to the items the API returns.
+
+use serde_json::Value;
+
+use super::{
+    filter::Filter,
+    sort::{self, Sort},
+    Request,
+};
+
+/// How a [`Request`] is run: the filters and the sort that are pushed down to the API,
+/// and the ones that are evaluated by the client.
+///
+/// When something is evaluated by the client, it fetches all the items that match the
+/// part pushed down to the API, and then filters, sorts and paginates them itself.
+///
+/// # Example
+/// ```
+/// use lotr_api::{ItemType, RequestBuilder,
+///     attribute::{Attribute, CharacterAttribute},
+///     filter::{Filter, Operator}};
+///
+/// let realm = Attribute::Character(CharacterAttribute::Realm);
+/// let request = RequestBuilder::new(ItemType::Character)
+///     .filter(Filter::Exists(Attribute::Character(CharacterAttribute::Spouse), true))
+///     .filter(Filter::Or(vec![
+///         Filter::Match(realm, Operator::Eq, vec!["Gondor".into()]),
+///         Filter::Exists(Attribute::Character(CharacterAttribute::Hair), false),
+///     ]))
+///     .build()
+///     .unwrap();
+///
+/// let plan = request.plan();
+/// assert_eq!(plan.remote_filters().len(), 1);
+/// assert_eq!(plan.local_filters().len(), 1);
+/// assert!(!plan.is_remote());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// The request sent to the API, without the pagination if the client
+    /// has to evaluate something.
+    remote: Request,
+    local_filters: Vec<Filter>,
+    local_sorts: Vec<Sort>,
+}
+
+impl Plan {
+    pub(crate) fn new(request: &Request) -> Self {
+        let mut conjuncts = Vec::new();
+        for filter in &request.filters {
+            flatten(filter, &mut conjuncts);
+        }
+
+        let mut remote_filters = Vec::new();
+        let mut local_filters = Vec::new();
+        for filter in conjuncts {
+            match filter.to_api() {
+                Some(filters) => remote_filters.extend(filters),
+                None => local_filters.push(filter.clone()),
+            }
+        }
+
+        let mut sorts = request.sorts.iter().copied();
+        let remote_sorts = sorts.next().into_iter().collect();
+        let local_sorts: Vec<_> = sorts.collect();
+
+        let is_remote = local_filters.is_empty() && local_sorts.is_empty();
+        let remote = Request {
+            sorts: remote_sorts,
+            filters: remote_filters,
+            pagination: request.pagination.filter(|_| is_remote),
+            ..request.clone()
+        };
+
+        Self {
+            remote,
+            local_filters,
+            local_sorts,
+        }
+    }
+
+    /// Returns the filters that are pushed down to the API.
+    pub fn remote_filters(&self) -> &[Filter] {
+        &self.remote.filters
+    }
+
+    /// Returns the filters that the API can't express, which are evaluated by the client.
+    pub fn local_filters(&self) -> &[Filter] {
+        &self.local_filters
+    }
+
+    /// Returns the sort that is pushed down to the API.
+    pub fn remote_sort(&self) -> Option<&Sort> {
+        self.remote.sorts.first()
+    }
+
+    /// Returns the sorts that follow the first one, which are applied by the client.
+    pub fn local_sorts(&self) -> &[Sort] {
+        &self.local_sorts
+    }
+
+    /// Returns `true` if the whole request is pushed down to the API.
+    pub fn is_remote(&self) -> bool {
+        self.local_filters.is_empty() && self.local_sorts.is_empty()
+    }
+
+    /// Returns the request that is sent to the API.
+    pub(crate) fn remote_request(&self) -> &Request {
+        &self.remote
+    }
+
+    /// Applies the filters and the sorts evaluated by the client to the items, given as
+    /// the JSON returned by the API for the remote request.
+    pub(crate) fn apply(&self, items: Vec<Value>) -> Vec<Value> {
+        let mut items: Vec<_> = items
+            .into_iter()
+            .filter(|item| self.local_filters.iter().all(|filter| filter.matches(item)))
+            .collect();
+        if !self.local_sorts.is_empty() {
+            let sorts: Vec<_> = self
+                .remote
+                .sorts
+                .iter()
+                .chain(&self.local_sorts)
+                .copied()
+                .collect();
+            items.sort_by(|a, b| sort::compare(&sorts, a, b));
+        }
+        items
+    }
+}
+
+/// Splits the filter into the filters that must all match.
+fn flatten<'a>(filter: &'a Filter, conjuncts: &mut Vec<&'a Filter>) {
+    match filter {
+        Filter::And(filters) => filters.iter().for_each(|filter| flatten(filter, conjuncts)),
+        filter => conjuncts.push(filter),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        attribute::{Attribute, CharacterAttribute, MovieAttribute},
+        filter::Operator,
+        request::{sort::SortOrder, GetUrl, RequestBuilder},
+        ItemType,
+    };
+
+    use super::*;
+
+    fn realm(realm: &str) -> Filter {
+        Filter::Match(
+            Attribute::Character(CharacterAttribute::Realm),
+            Operator::Eq,
+            vec![realm.into()],
+        )
+    }
+
+    fn spouse(exists: bool) -> Filter {
+        Filter::Exists(Attribute::Character(CharacterAttribute::Spouse), exists)
+    }
+
+    #[test]
+    fn test_push_down() {
+        let request = RequestBuilder::new(ItemType::Character)
+            .filter(Filter::And(vec![
+                Filter::Or(vec![realm("Gondor"), realm("Rohan")]),
+                Filter::Not(Box::new(spouse(true))),
+            ]))
+            .filter(Filter::Not(Box::new(Filter::Or(vec![
+                realm("Mordor"),
+                realm("Isengard"),
+            ]))))
+            .filter(Filter::Not(Box::new(Filter::Not(Box::new(realm("Arnor"))))))
+            .pagination(crate::Pagination::new(10, 0, 1))
+            .build()
+            .unwrap();
+
+        let plan = request.plan();
+        assert!(plan.is_remote());
+        assert!(plan.local_filters().is_empty());
+        assert_eq!(
+            request.get_url(),
+            "character?realm=Gondor,Rohan&!spouse&realm!=Mordor,Isengard&realm=Arnor&limit=10&page=1"
+        );
+        assert_eq!(plan.remote_request().get_url(), request.get_url());
+    }
+
+    #[test]
+    fn test_local_filters() {
+        let or = Filter::Or(vec![realm("Gondor"), spouse(true)]);
+        let budget = Filter::Not(Box::new(Filter::Match(
+            Attribute::Movie(MovieAttribute::BudgetInMillions),
+            Operator::Gt,
+            vec![100.into()],
+        )));
+        let request = RequestBuilder::new(ItemType::Character)
+            .filter(Filter::And(vec![realm("Rohan"), or.clone()]))
+            .filter(Filter::Not(Box::new(Filter::And(vec![
+                realm("Rohan"),
+                spouse(false),
+            ]))))
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Character(CharacterAttribute::Name),
+            ))
+            .pagination(crate::Pagination::new(10, 0, 1))
+            .build()
+            .unwrap();
+
+        let plan = request.plan();
+        assert!(!plan.is_remote());
+        assert_eq!(plan.remote_filters(), &[realm("Rohan")]);
+        assert_eq!(plan.local_filters().len(), 2);
+        assert_eq!(plan.local_filters()[0], or);
+        assert!(plan.remote_sort().is_some());
+        assert!(plan.local_sorts().is_empty());
+        // The client paginates the items itself.
+        assert_eq!(
+            plan.remote_request().get_url(),
+            "character?sort=name:asc&realm=Rohan"
+        );
+
+        // Only `=` and `!=` can be negated, the items without the attribute match `!(a > 1)`
+        // but not `a <= 1`.
+        assert_eq!(budget.to_api(), None);
+    }
+}
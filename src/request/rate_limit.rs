@@ -0,0 +1,199 @@
+//! Client side rate limiting.
+//!
+//! The API only allows a limited number of requests per token ( roughly 100 requests
+//! every 10 minutes ). A [`RateLimit`] can be set on the [`crate::ClientBuilder`] so that
+//! the client keeps track of its own requests and waits ( or fails ) before the API
+//! starts rejecting them.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::Error;
+
+/// What the client does when a request is made and there is no budget left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// Wait until there is budget for the request.
+    Wait,
+    /// Return an [`Error::RateLimited`] without making the request.
+    Reject,
+}
+
+/// The configuration of the rate limiter: a budget of requests that is
+/// refilled over a window of time.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use lotr_api::{ClientBuilder, RateLimit, request::rate_limit::RateLimitMode};
+///
+/// let client = ClientBuilder::new("your_token".to_string())
+///     .rate_limit(RateLimit::new(100, Duration::from_secs(600)).mode(RateLimitMode::Reject))
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(client.remaining_requests(), Some(100));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    budget: u32,
+    window: Duration,
+    mode: RateLimitMode,
+}
+
+impl RateLimit {
+    /// Allows `budget` requests every `window`. By default the client waits
+    /// when the budget is exhausted.
+    pub fn new(budget: u32, window: Duration) -> Self {
+        Self {
+            budget,
+            window,
+            mode: RateLimitMode::Wait,
+        }
+    }
+
+    /// Sets what happens when the budget is exhausted.
+    pub fn mode(mut self, mode: RateLimitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Checks that the budget and the window are not zero, a limiter without
+    /// either could never let a request through.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.budget == 0 || self.window.is_zero() {
+            return Err(Error::InvalidRateLimit {
+                budget: self.budget,
+                window: self.window,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for RateLimit {
+    /// The quota of the API: 100 requests every 10 minutes.
+    fn default() -> Self {
+        Self::new(100, Duration::from_secs(600))
+    }
+}
+
+/// Token bucket that implements a [`RateLimit`].
+/// It is shared by all the clones of a client.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimit,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(config: RateLimit) -> Self {
+        Self {
+            config,
+            bucket: Mutex::new(Bucket {
+                tokens: config.budget as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Number of tokens that are added to the bucket every second.
+    fn refill_rate(&self) -> f64 {
+        self.config.budget as f64 / self.config.window.as_secs_f64()
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens =
+            (bucket.tokens + elapsed * self.refill_rate()).min(self.config.budget as f64);
+        bucket.last_refill = now;
+    }
+
+    /// Takes a token from the bucket. If there is none, returns how long
+    /// it takes for the next one to be available.
+    fn try_acquire(&self) -> Result<(), Duration> {
+        let mut bucket = self.bucket.lock().expect("Rate limiter lock poisoned");
+        self.refill(&mut bucket);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.refill_rate(),
+            ))
+        }
+    }
+
+    /// Waits until a request can be made, or fails if the limiter rejects
+    /// requests once the budget is exhausted.
+    pub(crate) async fn acquire(&self) -> Result<(), Error> {
+        loop {
+            match self.try_acquire() {
+                Ok(()) => return Ok(()),
+                Err(wait) => match self.config.mode {
+                    RateLimitMode::Wait => tokio::time::sleep(wait).await,
+                    RateLimitMode::Reject => {
+                        return Err(Error::RateLimited {
+                            retry_after: Some(wait),
+                        })
+                    }
+                },
+            }
+        }
+    }
+
+    /// Returns the number of requests that can be made right now.
+    pub(crate) fn remaining(&self) -> u32 {
+        let mut bucket = self.bucket.lock().expect("Rate limiter lock poisoned");
+        self.refill(&mut bucket);
+        bucket.tokens.floor() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_is_consumed() {
+        let limiter = RateLimiter::new(RateLimit::new(2, Duration::from_secs(600)));
+        assert_eq!(limiter.remaining(), 2);
+        assert!(limiter.try_acquire().is_ok());
+        assert!(limiter.try_acquire().is_ok());
+        assert_eq!(limiter.remaining(), 0);
+
+        let wait = limiter.try_acquire().unwrap_err();
+        assert!(wait > Duration::from_secs(290) && wait <= Duration::from_secs(300));
+    }
+
+    #[tokio::test]
+    async fn test_reject_mode() {
+        let limiter = RateLimiter::new(
+            RateLimit::new(1, Duration::from_secs(600)).mode(RateLimitMode::Reject),
+        );
+        assert!(limiter.acquire().await.is_ok());
+        assert!(matches!(
+            limiter.acquire().await,
+            Err(Error::RateLimited { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_wait_mode() {
+        let limiter = RateLimiter::new(RateLimit::new(1, Duration::from_millis(50)));
+        let start = Instant::now();
+        assert!(limiter.acquire().await.is_ok());
+        assert!(limiter.acquire().await.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+}
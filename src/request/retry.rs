@@ -0,0 +1,331 @@
+//! Retry policy of the client.
+//!
+//! When the API answers with a status that is worth retrying ( too many requests,
+//! server errors... ) or the connection fails, the client waits and tries again
+//! according to its [`RetryPolicy`].
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, SystemTime},
+};
+
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    StatusCode,
+};
+
+use crate::Error;
+
+/// Header sent by the API with the time ( in seconds since the epoch ) at which
+/// the rate limit is reset.
+const RATE_LIMIT_RESET: &str = "x-ratelimit-reset";
+
+/// Header sent by the API with the number of requests left before the rate limit is reset.
+const RATE_LIMIT_REMAINING: &str = "x-ratelimit-remaining";
+
+/// Defines when and how a failed request is retried.
+///
+/// The delay between attempts grows exponentially from `base_delay` up to `max_delay`,
+/// with some random jitter. If the API tells how long to wait with the `Retry-After`
+/// header, or with the `X-RateLimit-Reset` header when the rate limit is exhausted,
+/// that delay is used instead, unless it is longer than `max_delay`, in which case
+/// the request is not retried.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use lotr_api::{ClientBuilder, RetryPolicy};
+///
+/// let client = ClientBuilder::new("your_token".to_string())
+///     .retry_policy(
+///         RetryPolicy::new(5)
+///             .base_delay(Duration::from_millis(200))
+///             .max_delay(Duration::from_secs(10))
+///             .retry_statuses(vec![429, 503]),
+///     )
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_statuses: Vec<u16>,
+    retry_on_timeout: bool,
+    retry_on_connect: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy that makes at most `max_attempts` attempts ( the first
+    /// one included ), with the default delays and retryable errors.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_statuses: vec![429, 500, 502, 503, 504],
+            retry_on_timeout: true,
+            retry_on_connect: true,
+        }
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Sets the delay before the first retry. It doubles on every attempt.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sets the maximum delay between two attempts.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Sets whether the delays are randomized, to avoid many clients retrying at the same time.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the HTTP statuses that are retried.
+    pub fn retry_statuses(mut self, retry_statuses: Vec<u16>) -> Self {
+        self.retry_statuses = retry_statuses;
+        self
+    }
+
+    /// Sets whether requests that time out are retried.
+    pub fn retry_on_timeout(mut self, retry_on_timeout: bool) -> Self {
+        self.retry_on_timeout = retry_on_timeout;
+        self
+    }
+
+    /// Sets whether requests that fail to connect are retried.
+    pub fn retry_on_connect(mut self, retry_on_connect: bool) -> Self {
+        self.retry_on_connect = retry_on_connect;
+        self
+    }
+
+    pub(crate) fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub(crate) fn retries_status(&self, status: StatusCode) -> bool {
+        self.retry_statuses.contains(&status.as_u16())
+    }
+
+    pub(crate) fn retries_error(&self, error: &Error) -> bool {
+        match error {
+            Error::Reqwest(error) => {
+                (self.retry_on_timeout && error.is_timeout())
+                    || (self.retry_on_connect && error.is_connect())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the delay before the attempt that follows the given one.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        if self.jitter {
+            // Keep half of the delay and randomize the other half.
+            let half = delay / 2;
+            half + half.mul_f64(random())
+        } else {
+            delay
+        }
+    }
+
+    /// Returns the delay before retrying a request that failed with the given status
+    /// and headers, or `None` if the API asks to wait longer than the maximum delay.
+    pub(crate) fn delay_for_response(
+        &self,
+        attempt: u32,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Option<Duration> {
+        match server_delay(status, headers) {
+            Some(delay) if delay > self.max_delay => None,
+            Some(delay) => Some(delay),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, starting with a delay of 500ms.
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Reads how long the API asks to wait from the `Retry-After` header ( either
+/// in seconds or as a date ) or, for a `429 Too Many Requests` with no request
+/// remaining, from the `X-RateLimit-Reset` header.
+///
+/// The reset header is sent with every response, so on other statuses it only
+/// tells when the current window ends, not when to retry.
+pub(crate) fn server_delay(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if let Some(value) = headers.get(RETRY_AFTER).and_then(|v| v.to_str().ok()) {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        if let Ok(date) = httpdate::parse_http_date(value) {
+            return Some(
+                date.duration_since(SystemTime::now())
+                    .unwrap_or(Duration::ZERO),
+            );
+        }
+    }
+
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let remaining = headers
+        .get(RATE_LIMIT_REMAINING)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    if remaining.is_some_and(|remaining| remaining > 0) {
+        return None;
+    }
+
+    let reset = headers
+        .get(RATE_LIMIT_RESET)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())?;
+    let reset = SystemTime::UNIX_EPOCH + Duration::from_secs(reset);
+    Some(
+        reset
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Returns a random number in `[0, 1)`.
+fn random() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_backoff_without_jitter() {
+        let policy = RetryPolicy::new(10)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1))
+            .jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_backoff_with_jitter() {
+        let policy = RetryPolicy::new(10).base_delay(Duration::from_millis(100));
+        for _ in 0..100 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let policy = RetryPolicy::new(3).max_delay(Duration::from_secs(60));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("12"));
+        assert_eq!(
+            policy.delay_for_response(1, StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(12))
+        );
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(
+            policy.delay_for_response(1, StatusCode::TOO_MANY_REQUESTS, &headers),
+            None
+        );
+
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(30));
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+        let delay = policy
+            .delay_for_response(1, StatusCode::TOO_MANY_REQUESTS, &headers)
+            .unwrap();
+        assert!(delay > Duration::from_secs(25) && delay <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_rate_limit_reset_header() {
+        let policy = RetryPolicy::new(3).max_delay(Duration::from_secs(60));
+        let reset = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 20;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            RATE_LIMIT_RESET,
+            HeaderValue::from_str(&reset.to_string()).unwrap(),
+        );
+        let delay = policy
+            .delay_for_response(1, StatusCode::TOO_MANY_REQUESTS, &headers)
+            .unwrap();
+        assert!(delay > Duration::from_secs(15) && delay <= Duration::from_secs(20));
+
+        // Requests are left, so the reset is not the time to wait.
+        headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from_static("3"));
+        let delay = policy
+            .delay_for_response(1, StatusCode::TOO_MANY_REQUESTS, &headers)
+            .unwrap();
+        assert!(delay < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_rate_limit_reset_header_other_status() {
+        let policy = RetryPolicy::new(3).max_delay(Duration::from_secs(60));
+        let reset = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3600;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            RATE_LIMIT_RESET,
+            HeaderValue::from_str(&reset.to_string()).unwrap(),
+        );
+        headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from_static("0"));
+        let delay = policy
+            .delay_for_response(1, StatusCode::SERVICE_UNAVAILABLE, &headers)
+            .unwrap();
+        assert!(delay < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,494 @@
+//! The serde representation of the requests.
+//!
+//! Requests are stored with a `version` field, so that a request saved by an older
+//! version of the crate can still be read after the format changes. When it does,
+//! [`FORMAT_VERSION`] is bumped and the older versions are converted on deserialization.
+//!
+//! The versions are:
+//! 1. The first format, where the values of the filters are strings.
+//! 2. The values of the filters are typed: numbers, strings or ids ( `{"id": "..."}` ).
+//! 3. The request holds a list of `sorts` instead of a single `sort`.
+//! 4. The filters can be combined with the `and`, `or` and `not` kinds.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Error, ItemType};
+
+use super::{
+    filter::{Filter, FilterValue, Operator},
+    pagination::Pagination,
+    sort::Sort,
+    Request,
+};
+
+/// The current version of the serialized requests.
+pub const FORMAT_VERSION: u32 = 4;
+
+/// A serialized [`Request`].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SerializedRequest {
+    version: u32,
+    item_type: ItemType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secondary_item_type: Option<ItemType>,
+    /// The sort of the versions 1 and 2.
+    #[serde(default, skip_serializing)]
+    sort: Option<Sort>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sorts: Vec<Sort>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    filters: Vec<Filter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pagination: Option<Pagination>,
+}
+
+impl From<Request> for SerializedRequest {
+    fn from(request: Request) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            item_type: request.item_type,
+            id: request.id,
+            secondary_item_type: request.secondary_item_type,
+            sort: None,
+            sorts: request.sorts,
+            filters: request.filters,
+            pagination: request.pagination,
+        }
+    }
+}
+
+impl TryFrom<SerializedRequest> for Request {
+    type Error = Error;
+
+    fn try_from(serialized: SerializedRequest) -> Result<Self, Self::Error> {
+        if serialized.version == 0 || serialized.version > FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion(serialized.version));
+        }
+
+        let mut filters = serialized.filters;
+        if serialized.version == 1 {
+            filters = filters.into_iter().map(typed_values).collect();
+        }
+        let mut sorts = serialized.sorts;
+        if serialized.version < 3 {
+            sorts = serialized.sort.into_iter().collect();
+        }
+
+        Request {
+            item_type: serialized.item_type,
+            id: serialized.id,
+            secondary_item_type: serialized.secondary_item_type,
+            sorts,
+            filters,
+            pagination: serialized.pagination.filter(|p| !p.is_empty()),
+        }
+        .validate()
+    }
+}
+
+/// Converts the values of a filter saved with the version 1, which are all strings,
+/// into values of the kind of the attribute.
+fn typed_values(filter: Filter) -> Filter {
+    match filter {
+        Filter::Match(attribute, operator, values) => {
+            let kind = attribute.value_kind();
+            let values = values
+                .into_iter()
+                .map(|value| match value {
+                    FilterValue::String(value) => FilterValue::from_url(kind, value),
+                    value => value,
+                })
+                .collect();
+            Filter::Match(attribute, operator, values)
+        }
+        filter => filter,
+    }
+}
+
+/// A serialized [`FilterValue`]. Numbers and strings are written as they are,
+/// and ids are written as `{"id": "..."}`.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum SerializedValue {
+    Number(f64),
+    String(String),
+    Id { id: String },
+}
+
+impl Serialize for FilterValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.clone() {
+            FilterValue::Number(number) => SerializedValue::Number(number.get()),
+            FilterValue::String(value) => SerializedValue::String(value),
+            FilterValue::Id(id) => SerializedValue::Id { id },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FilterValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match SerializedValue::deserialize(deserializer)? {
+            SerializedValue::Number(number) => {
+                FilterValue::try_from(number).map_err(serde::de::Error::custom)?
+            }
+            SerializedValue::String(value) => FilterValue::String(value),
+            SerializedValue::Id { id } => FilterValue::Id(id),
+        })
+    }
+}
+
+/// A serialized [`Filter`], tagged with its kind.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "lowercase",
+    bound(
+        serialize = "A: Serialize + Clone",
+        deserialize = "A: Deserialize<'de>"
+    )
+)]
+enum SerializedFilter<A> {
+    Match {
+        attribute: A,
+        operator: Operator,
+        values: Vec<FilterValue>,
+    },
+    Exists {
+        attribute: A,
+        exists: bool,
+    },
+    Regex {
+        attribute: A,
+        operator: Operator,
+        pattern: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        flags: String,
+    },
+    And {
+        filters: Vec<Filter<A>>,
+    },
+    Or {
+        filters: Vec<Filter<A>>,
+    },
+    Not {
+        filter: Box<Filter<A>>,
+    },
+}
+
+impl<A> From<Filter<A>> for SerializedFilter<A> {
+    fn from(filter: Filter<A>) -> Self {
+        match filter {
+            Filter::Match(attribute, operator, values) => Self::Match {
+                attribute,
+                operator,
+                values,
+            },
+            Filter::Exists(attribute, exists) => Self::Exists { attribute, exists },
+            Filter::Regex(attribute, operator, pattern, flags) => Self::Regex {
+                attribute,
+                operator,
+                pattern,
+                flags,
+            },
+            Filter::And(filters) => Self::And { filters },
+            Filter::Or(filters) => Self::Or { filters },
+            Filter::Not(filter) => Self::Not { filter },
+        }
+    }
+}
+
+impl<A> From<SerializedFilter<A>> for Filter<A> {
+    fn from(filter: SerializedFilter<A>) -> Self {
+        match filter {
+            SerializedFilter::Match {
+                attribute,
+                operator,
+                values,
+            } => Filter::Match(attribute, operator, values),
+            SerializedFilter::Exists { attribute, exists } => Filter::Exists(attribute, exists),
+            SerializedFilter::Regex {
+                attribute,
+                operator,
+                pattern,
+                flags,
+            } => Filter::Regex(attribute, operator, pattern, flags),
+            SerializedFilter::And { filters } => Filter::And(filters),
+            SerializedFilter::Or { filters } => Filter::Or(filters),
+            SerializedFilter::Not { filter } => Filter::Not(filter),
+        }
+    }
+}
+
+impl<A: Serialize + Clone> Serialize for Filter<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedFilter::from(self.clone()).serialize(serializer)
+    }
+}
+
+impl<'de, A: Deserialize<'de>> Deserialize<'de> for Filter<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SerializedFilter::deserialize(deserializer).map(Filter::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        attribute::{Attribute, BookAttribute, CharacterAttribute, QuoteAttribute},
+        request::{sort::SortOrder, RequestBuilder},
+        CharacterId,
+    };
+
+    use super::*;
+
+    fn request() -> Request {
+        RequestBuilder::new(ItemType::Character)
+            .id(CharacterId::new("5cd99d4bde30eff6ebccfea0").unwrap())
+            .secondary_item_type(ItemType::Quote)
+            .sort(Sort::new(
+                SortOrder::Descending,
+                Attribute::Quote(QuoteAttribute::Dialog),
+            ))
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Quote(QuoteAttribute::Id),
+            ))
+            .filter(Filter::Match(
+                Attribute::Quote(QuoteAttribute::Dialog),
+                Operator::Ne,
+                vec!["Deagol!".into()],
+            ))
+            .filter(Filter::Exists(
+                Attribute::Quote(QuoteAttribute::Movie),
+                true,
+            ))
+            .filter(Filter::Regex(
+                Attribute::Quote(QuoteAttribute::Dialog),
+                Operator::Eq,
+                "ring".to_string(),
+                "i".to_string(),
+            ))
+            .filter(Filter::Match(
+                Attribute::Quote(QuoteAttribute::Movie),
+                Operator::Eq,
+                vec![FilterValue::id("5cd95395de30eff6ebccde5d")],
+            ))
+            .pagination(Pagination::new(10, 0, 2))
+            .build()
+            .unwrap()
+    }
+
+    const JSON: &str = r#"{
+  "version": 4,
+  "item_type": "character",
+  "id": "5cd99d4bde30eff6ebccfea0",
+  "secondary_item_type": "quote",
+  "sorts": [
+    {
+      "order": "desc",
+      "by": {
+        "quote": "dialog"
+      }
+    },
+    {
+      "order": "asc",
+      "by": {
+        "quote": "_id"
+      }
+    }
+  ],
+  "filters": [
+    {
+      "kind": "match",
+      "attribute": {
+        "quote": "dialog"
+      },
+      "operator": "ne",
+      "values": [
+        "Deagol!"
+      ]
+    },
+    {
+      "kind": "exists",
+      "attribute": {
+        "quote": "movie"
+      },
+      "exists": true
+    },
+    {
+      "kind": "regex",
+      "attribute": {
+        "quote": "dialog"
+      },
+      "operator": "eq",
+      "pattern": "ring",
+      "flags": "i"
+    },
+    {
+      "kind": "match",
+      "attribute": {
+        "quote": "movie"
+      },
+      "operator": "eq",
+      "values": [
+        {
+          "id": "5cd95395de30eff6ebccde5d"
+        }
+      ]
+    }
+  ],
+  "pagination": {
+    "limit": 10,
+    "offset": 0,
+    "page": 2
+  }
+}"#;
+
+    #[test]
+    fn test_serialize() {
+        assert_eq!(serde_json::to_string_pretty(&request()).unwrap(), JSON);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(serde_json::from_str::<Request>(JSON).unwrap(), request());
+    }
+
+    #[test]
+    fn test_deserialize_minimal() {
+        let request: Request = serde_json::from_str(
+            r#"{"version": 1, "item_type": "character", "pagination": {"limit": 5}}"#,
+        )
+        .unwrap();
+        let expected = RequestBuilder::new(ItemType::Character)
+            .pagination(Pagination::new(5, 0, 0))
+            .build()
+            .unwrap();
+        assert_eq!(request, expected);
+    }
+
+    #[test]
+    fn test_deserialize_version_1() {
+        let json = r#"{
+            "version": 1,
+            "item_type": "quote",
+            "filters": [
+                {"kind": "match", "attribute": {"quote": "movie"}, "operator": "eq", "values": ["5cd95395de30eff6ebccde5d"]},
+                {"kind": "match", "attribute": {"quote": "dialog"}, "operator": "ne", "values": ["Deagol!"]}
+            ]
+        }"#;
+        let request: Request = serde_json::from_str(json).unwrap();
+        let expected = RequestBuilder::new(ItemType::Quote)
+            .filter(Filter::Match(
+                Attribute::Quote(QuoteAttribute::Movie),
+                Operator::Eq,
+                vec![FilterValue::id("5cd95395de30eff6ebccde5d")],
+            ))
+            .filter(Filter::Match(
+                Attribute::Quote(QuoteAttribute::Dialog),
+                Operator::Ne,
+                vec!["Deagol!".into()],
+            ))
+            .build()
+            .unwrap();
+        assert_eq!(request, expected);
+
+        let json = r#"{
+            "version": 1,
+            "item_type": "movie",
+            "filters": [{"kind": "match", "attribute": {"movie": "budgetInMillions"}, "operator": "gte", "values": ["100"]}]
+        }"#;
+        let request: Request = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"version":4,"item_type":"movie","filters":[{"kind":"match","attribute":{"movie":"budgetInMillions"},"operator":"gte","values":[100.0]}]}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_version_2() {
+        let json = r#"{
+            "version": 2,
+            "item_type": "book",
+            "sort": {"order": "asc", "by": {"book": "name"}}
+        }"#;
+        let request: Request = serde_json::from_str(json).unwrap();
+        let expected = RequestBuilder::new(ItemType::Book)
+            .sort(Sort::new(
+                SortOrder::Ascending,
+                Attribute::Book(BookAttribute::Name),
+            ))
+            .build()
+            .unwrap();
+        assert_eq!(request, expected);
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"version":4,"item_type":"book","sorts":[{"order":"asc","by":{"book":"name"}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_version_3() {
+        let json = JSON.replace("\"version\": 4", "\"version\": 3");
+        assert_eq!(serde_json::from_str::<Request>(&json).unwrap(), request());
+    }
+
+    #[test]
+    fn test_combinators() {
+        let filter = Filter::Or(vec![
+            Filter::Not(Box::new(Filter::Exists(CharacterAttribute::Spouse, true))),
+            Filter::And(vec![Filter::Match(
+                CharacterAttribute::Realm,
+                Operator::Eq,
+                vec!["Gondor".into()],
+            )]),
+        ]);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"or","filters":[{"kind":"not","filter":{"kind":"exists","attribute":"spouse","exists":true}},{"kind":"and","filters":[{"kind":"match","attribute":"realm","operator":"eq","values":["Gondor"]}]}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Filter<CharacterAttribute>>(&json).unwrap(),
+            filter
+        );
+    }
+
+    #[test]
+    fn test_typed_attributes() {
+        let filter = Filter::Exists(CharacterAttribute::WikiUrl, false);
+        let json = serde_json::to_string(&filter).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"exists","attribute":"wikiUrl","exists":false}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Filter<CharacterAttribute>>(&json).unwrap(),
+            filter
+        );
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let json = JSON.replace("\"version\": 4", "\"version\": 5");
+        let error = serde_json::from_str::<Request>(&json).unwrap_err();
+        assert!(error.to_string().contains("version 5"), "{}", error);
+
+        let json = JSON.replace("  \"version\": 4,\n", "");
+        assert!(serde_json::from_str::<Request>(&json).is_err());
+    }
+
+    #[test]
+    fn test_invalid_request() {
+        // The filter is on a book attribute, but the request returns characters.
+        let json = r#"{
+            "version": 1,
+            "item_type": "character",
+            "filters": [{"kind": "exists", "attribute": {"book": "name"}, "exists": true}]
+        }"#;
+        assert!(serde_json::from_str::<Request>(json).is_err());
+    }
+}
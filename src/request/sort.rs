@@ -1,3 +1,8 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 use crate::attribute::Attribute;
 
 use super::GetUrl;
@@ -14,13 +19,30 @@ use super::GetUrl;
 /// let sort = Sort::new(SortOrder::Ascending, Attribute::Book(BookAttribute::Name));
 /// assert_eq!(sort.get_url(), "sort=name:asc");
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct Sort {
+///
+/// The attribute can also be the attribute of a specific item, which is what
+/// typed requests expect ( see [`crate::RequestBuilder::typed`] ).
+///
+/// ```
+/// use lotr_api::{
+///     attribute::BookAttribute,
+///     sort::{Sort, SortOrder},
+///     request::{GetUrl}};
+///
+/// let sort = Sort::new(SortOrder::Descending, BookAttribute::Name);
+/// assert_eq!(sort.get_url(), "sort=name:desc");
+/// ```
+///
+/// A request can be sorted by several attributes, see [`crate::RequestBuilder::sort`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sort<A = Attribute> {
+    #[serde(rename = "order")]
     pub(crate) sort_type: SortOrder,
-    pub(crate) sort_by: Attribute,
+    #[serde(rename = "by")]
+    pub(crate) sort_by: A,
 }
 
-impl GetUrl for Sort {
+impl<A: GetUrl> GetUrl for Sort<A> {
     fn get_url(&self) -> String {
         let mut url = String::from("sort=");
         url.push_str(format!("{}:{}", self.sort_by.get_url(), self.sort_type.get_url()).as_str());
@@ -28,20 +50,73 @@ impl GetUrl for Sort {
     }
 }
 
-impl Sort {
-    pub fn new(sort_type: SortOrder, sort_by: Attribute) -> Self {
+impl<A> Sort<A> {
+    pub fn new(sort_type: SortOrder, sort_by: A) -> Self {
         Self { sort_type, sort_by }
     }
+}
 
+impl<A: Into<Attribute>> Sort<A> {
+    /// Converts the sort of a specific item into a sort over [`Attribute`].
+    pub(crate) fn into_attribute(self) -> Sort {
+        Sort::new(self.sort_type, self.sort_by.into())
+    }
+}
+
+impl Sort {
     pub(crate) fn get_item_type(&self) -> crate::ItemType {
         self.sort_by.get_item_type()
     }
+
+    /// Compares two items, given as the JSON returned by the API, by the attribute of the sort.
+    ///
+    /// The values are ordered like the API does: missing values first, then numbers and
+    /// then strings.
+    pub(crate) fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        let name = self.sort_by.get_url();
+        let ordering = compare_values(a.get(&name), b.get(&name));
+        match self.sort_type {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// Compares the items by each of the sorts in turn, the first one being the primary key.
+pub(crate) fn compare(sorts: &[Sort], a: &Value, b: &Value) -> Ordering {
+    sorts
+        .iter()
+        .map(|sort| sort.compare(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    fn rank(value: Option<&Value>) -> u8 {
+        match value {
+            None | Some(Value::Null) => 0,
+            Some(Value::Number(_)) => 1,
+            Some(Value::String(_)) => 2,
+            Some(_) => 3,
+        }
+    }
+
+    match (a, b) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
 }
 
 /// Define the sort order.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SortOrder {
+    #[serde(rename = "asc")]
     Ascending,
+    #[serde(rename = "desc")]
     Descending,
 }
 
@@ -54,3 +129,43 @@ impl GetUrl for SortOrder {
         .to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::attribute::{Attribute, MovieAttribute};
+
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        let sorts = [
+            Sort::new(
+                SortOrder::Descending,
+                Attribute::Movie(MovieAttribute::AcademyAwardWins),
+            ),
+            Sort::new(SortOrder::Ascending, Attribute::Movie(MovieAttribute::Name)),
+        ];
+        let mut movies = [
+            json!({"name": "The Two Towers", "academyAwardWins": 2}),
+            json!({"name": "The Hobbit", "academyAwardWins": null}),
+            json!({"name": "The Return of the King", "academyAwardWins": 11}),
+            json!({"name": "The Fellowship of the Ring", "academyAwardWins": 2.0}),
+            json!({"name": "The Desolation of Smaug"}),
+        ];
+        movies.sort_by(|a, b| compare(&sorts, a, b));
+
+        let names: Vec<_> = movies.iter().map(|movie| &movie["name"]).collect();
+        assert_eq!(
+            names,
+            vec![
+                "The Return of the King",
+                "The Fellowship of the Ring",
+                "The Two Towers",
+                "The Desolation of Smaug",
+                "The Hobbit",
+            ]
+        );
+    }
+}
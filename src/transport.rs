@@ -0,0 +1,188 @@
+//! The HTTP layer of the client.
+//!
+//! The [`crate::Client`] does not talk to the network directly, it sends its requests
+//! through a [`Transport`]. By default it uses [`ReqwestTransport`], but any other
+//! implementation can be given to the [`crate::ClientBuilder`]. This crate also ships
+//! [`FixtureTransport`], which answers with in-memory responses, to test code built on
+//! top of the client without a token or a network connection.
+//!
+//! # Example
+//!
+//! ```
+//! use lotr_api::{transport::FixtureTransport, ClientBuilder};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let transport = FixtureTransport::new().with_response(
+//!         "book",
+//!         r#"{"docs":[{"_id":"5cf5805fb53e011a64671582","name":"The Fellowship Of The Ring"}],
+//!             "total":1,"limit":1000,"offset":0,"page":1,"pages":1}"#,
+//!     );
+//!     let client = ClientBuilder::new("token".to_string())
+//!         .transport(transport)
+//!         .build()
+//!         .unwrap();
+//!
+//!     let books = client.get_books().await.unwrap();
+//!     assert_eq!(books[0].name, "The Fellowship Of The Ring");
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::{header::HeaderMap, StatusCode};
+
+use crate::Error;
+
+/// The future returned by [`Transport::get`].
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<TransportResponse, Error>> + Send + 'a>>;
+
+/// A request made by the client.
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    /// The full url of the request.
+    pub url: String,
+    /// The url of the request relative to the base url of the client,
+    /// for example `book?limit=10`.
+    pub path: String,
+    /// The headers of the request, the token included.
+    pub headers: HeaderMap,
+}
+
+/// A response to a [`TransportRequest`].
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl TransportResponse {
+    /// Creates a response with the given status and body and no headers.
+    pub fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// This trait is implemented by everything that can send the requests of the client.
+///
+/// The client takes care of the rate limit, the retries and the decoding of the responses,
+/// so a transport only has to send the request and return the response as is, whatever
+/// its status. Errors are reserved to requests that could not be made at all.
+pub trait Transport: Debug + Send + Sync {
+    /// Sends a `GET` request.
+    fn get(&self, request: TransportRequest) -> TransportFuture<'_>;
+}
+
+/// The default [`Transport`], backed by a [`reqwest::Client`].
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn get(&self, request: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .get(request.url)
+                .headers(request.headers)
+                .send()
+                .await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.text().await?;
+            Ok(TransportResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+/// A [`Transport`] that answers with responses registered in advance, matched by the
+/// path of the request ( the url relative to the base url, query included ).
+/// Requests without a registered response get a `404 Not Found`.
+///
+/// Clones share the same responses and the same record of requests.
+#[derive(Debug, Clone, Default)]
+pub struct FixtureTransport {
+    responses: Arc<Mutex<HashMap<String, TransportResponse>>>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FixtureTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers the requests to the given path with a successful response with the given body.
+    pub fn with_response(self, path: impl Into<String>, body: impl Into<String>) -> Self {
+        self.insert(path, TransportResponse::new(StatusCode::OK, body));
+        self
+    }
+
+    /// Answers the requests to the given path with the given status and body.
+    pub fn with_status(
+        self,
+        path: impl Into<String>,
+        status: StatusCode,
+        body: impl Into<String>,
+    ) -> Self {
+        self.insert(path, TransportResponse::new(status, body));
+        self
+    }
+
+    /// Answers the requests to the given path with the given response.
+    pub fn insert(&self, path: impl Into<String>, response: TransportResponse) {
+        self.responses
+            .lock()
+            .expect("Fixture lock poisoned")
+            .insert(path.into(), response);
+    }
+
+    /// Returns the paths of all the requests received so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().expect("Fixture lock poisoned").clone()
+    }
+}
+
+impl Transport for FixtureTransport {
+    fn get(&self, request: TransportRequest) -> TransportFuture<'_> {
+        self.requests
+            .lock()
+            .expect("Fixture lock poisoned")
+            .push(request.path.clone());
+        let response = self
+            .responses
+            .lock()
+            .expect("Fixture lock poisoned")
+            .get(&request.path)
+            .cloned()
+            .unwrap_or_else(|| {
+                TransportResponse::new(
+                    StatusCode::NOT_FOUND,
+                    format!("No fixture for {}", request.path),
+                )
+            });
+        Box::pin(async move { Ok(response) })
+    }
+}
@@ -1,3 +1,6 @@
+// The assertions predate the clippy gate and are kept as they were written.
+#![allow(clippy::len_zero)]
+
 use lotr_api::{
     attribute::{Attribute, BookAttribute, CharacterAttribute},
     filter::{Filter, Operator},
@@ -5,7 +8,7 @@ use lotr_api::{
         sort::{Sort, SortOrder},
         RequestBuilder,
     },
-    Client, Item, ItemType, Pagination,
+    Client, Item, ItemType, Pagination, Request,
 };
 
 pub fn get_client() -> Client {
@@ -37,7 +40,7 @@ async fn test_quote() {
     assert!(quotes.len() > 0);
 }
 
-// Reminder to update the quote limit if it ever changes
+// Checks that all the pages of quotes are retrieved
 #[tokio::test]
 async fn test_quote_number() {
     let client = get_client();
@@ -82,7 +85,7 @@ async fn tets_get_aragorn_ii_quote() {
         ._id;
 
     let request = RequestBuilder::new(ItemType::Character)
-        .id(id.into())
+        .id(id.clone())
         .secondary_item_type(ItemType::Quote)
         .build()
         .expect("Failed to build request");
@@ -130,7 +133,7 @@ async fn test_filter() {
         .filter(Filter::Match(
             Attribute::Book(BookAttribute::Name),
             Operator::Eq,
-            vec!["The Fellowship Of The Ring".to_string()],
+            vec!["The Fellowship Of The Ring".into()],
         ))
         .build()
         .expect("Failed to build request");
@@ -151,7 +154,7 @@ async fn test_filter_include() {
         .filter(Filter::Match(
             Attribute::Character(CharacterAttribute::Realm),
             Operator::Eq,
-            vec!["Gondor".to_string(), "Rohan".to_string()],
+            vec!["Gondor".into(), "Rohan".into()],
         ))
         .build()
         .expect("Failed to build request");
@@ -174,3 +177,29 @@ async fn test_filter_include() {
         }
     }
 }
+
+// Checks that `Request::apply` gives the response of the API, on the whole dataset
+// of movies.
+#[tokio::test]
+async fn test_apply_parity() {
+    let client = get_client();
+    let movies: Vec<Item> = client
+        .get_movies()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(Item::from)
+        .collect();
+
+    for url in [
+        "movie?budgetInMillions<100",
+        "movie?sort=runtimeInMinutes:desc&academyAwardWins>0",
+        "movie?name=/the%20(two|return)/i",
+        "movie?academyAwardNominations!=3,7",
+        "movie?sort=rottenTomatoesScore:desc&limit=3&page=2",
+    ] {
+        let request: Request = url.parse().unwrap();
+        let response = client.get(request.clone()).await.unwrap();
+        assert_eq!(request.apply(&movies), response, "{}", url);
+    }
+}
@@ -0,0 +1,217 @@
+//! Checks that `Request::apply` follows the semantics of the API, by applying
+//! requests to a dataset and comparing the result to the expected responses.
+//!
+//! The fixtures are written by hand, in the format of the responses of the API, with
+//! the items that match each url, so that the semantics are checked offline. The same
+//! urls are checked against the API itself by `test_apply_parity` in `basic_call.rs`.
+
+use lotr_api::{
+    attribute::MovieAttribute,
+    filter::{Filter, Operator},
+    lore::{Gender, Hair, Realm},
+    Character, Item, Movie, Request, RequestBuilder, TypedRequest,
+};
+use serde::de::DeserializeOwned;
+
+/// Returns the items of a response of the API.
+fn docs<T: DeserializeOwned + Into<Item>>(response: &str) -> Vec<Item> {
+    let mut response: serde_json::Value = serde_json::from_str(response).unwrap();
+    serde_json::from_value::<Vec<T>>(response["docs"].take())
+        .unwrap()
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+fn assert_parity<T: DeserializeOwned + Into<Item>>(dataset: &str, cases: &[(&str, &str)]) {
+    let items = docs::<T>(dataset);
+    for (url, response) in cases {
+        let request: Request = url.parse().unwrap();
+        assert_eq!(request.apply(&items), docs::<T>(response), "{}", url);
+    }
+}
+
+#[test]
+fn test_movies() {
+    assert_parity::<Movie>(
+        include_str!("fixtures/movie.json"),
+        &[
+            ("movie", include_str!("fixtures/movie.json")),
+            (
+                "movie?budgetInMillions<100",
+                include_str!("fixtures/movie_budget_lt_100.json"),
+            ),
+            (
+                "movie?sort=runtimeInMinutes:desc&academyAwardWins>0",
+                include_str!("fixtures/movie_wins_gt_0_sort_runtime_desc.json"),
+            ),
+            (
+                "movie?name=/the%20(two|return)/i",
+                include_str!("fixtures/movie_name_regex.json"),
+            ),
+            (
+                "movie?sort=rottenTomatoesScore:desc&limit=3&page=2",
+                include_str!("fixtures/movie_sort_score_desc_page_2.json"),
+            ),
+            (
+                "movie?academyAwardNominations!=3,7",
+                include_str!("fixtures/movie_nominations_ne.json"),
+            ),
+            (
+                "movie/5cd95395de30eff6ebccde5b",
+                include_str!("fixtures/movie_id.json"),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_characters() {
+    // Some characters have no spouse or realm, or have them set to `null`. Unlike
+    // for the API, `null` is deserialized as `None`, so it doesn't exist either.
+    assert_parity::<Character>(
+        include_str!("fixtures/character.json"),
+        &[
+            (
+                "character?!spouse",
+                include_str!("fixtures/character_no_spouse.json"),
+            ),
+            (
+                "character?realm",
+                include_str!("fixtures/character_realm.json"),
+            ),
+            (
+                "character?sort=realm:asc&realm!=Rohan",
+                include_str!("fixtures/character_realm_ne_sort_realm.json"),
+            ),
+            (
+                "character?sort=name:desc&hair=Dark",
+                include_str!("fixtures/character_hair_sort_name_desc.json"),
+            ),
+            (
+                "character?sort=name:asc&race=Human",
+                include_str!("fixtures/character_race_sort_name.json"),
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_typed_request() {
+    let movies: Vec<Movie> = docs::<Movie>(include_str!("fixtures/movie.json"))
+        .into_iter()
+        .map(|item| item.try_into().unwrap())
+        .collect();
+    let request = RequestBuilder::<Movie>::typed()
+        .filter(Filter::Or(vec![
+            Filter::Match(
+                MovieAttribute::AcademyAwardWins,
+                Operator::Gte,
+                vec![11.into()],
+            ),
+            Filter::Match(
+                MovieAttribute::BudgetInMillions,
+                Operator::Gt,
+                vec![600.into()],
+            ),
+        ]))
+        .build()
+        .unwrap();
+
+    let names: Vec<_> = request
+        .apply(&movies)
+        .into_iter()
+        .map(|movie| movie.name)
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "The Lord of the Rings Series",
+            "The Hobbit Series",
+            "The Return of the King"
+        ]
+    );
+}
+
+#[test]
+fn test_other_item_types_are_ignored() {
+    let mut items = docs::<Movie>(include_str!("fixtures/movie.json"));
+    items.extend(docs::<Character>(include_str!("fixtures/character.json")));
+
+    let request: Request = "character?hair=Dark".parse().unwrap();
+    assert_eq!(request.apply(&items).len(), 2);
+}
+
+#[test]
+fn test_normalized_values() {
+    let characters: Vec<Character> = docs::<Character>(include_str!("fixtures/character.json"))
+        .into_iter()
+        .map(|item| item.try_into().unwrap())
+        .collect();
+    let request = RequestBuilder::<Character>::typed()
+        .filter(Realm::filter_ne([Realm::Gondor]))
+        .build()
+        .unwrap();
+
+    let realms: Vec<_> = request
+        .apply(&characters)
+        .iter()
+        .map(Character::parsed_realm)
+        .collect();
+    assert_eq!(
+        realms,
+        vec![None, None, Some(Realm::Rivendell), Some(Realm::Rohan)]
+    );
+    assert!(characters
+        .iter()
+        .all(|character| character.parsed_gender().is_some()));
+}
+
+#[test]
+fn test_normalized_values_mixed_case() {
+    let characters: Vec<Character> = serde_json::from_str(
+        r#"[
+            {"_id": "5cd99d4bde30eff6ebccfbbe", "name": "Adanel", "gender": "Female", "hair": "Gray"},
+            {"_id": "5cd99d4bde30eff6ebccfbbf", "name": "Adrahil I", "gender": "Male", "hair": "grey"},
+            {"_id": "5cd99d4bde30eff6ebccfbc0", "name": "Adrahil II", "gender": "male", "hair": "Black"},
+            {"_id": "5cd99d4bde30eff6ebccfbc1", "name": "Aegnor", "gender": "Males", "hair": "Grey"},
+            {"_id": "5cd99d4bde30eff6ebccfbc2", "name": "Aerin", "gender": "females"},
+            {"_id": "5cd99d4bde30eff6ebccfbc3", "name": "Agarwaen", "gender": "NaN", "hair": "gray"}
+        ]"#,
+    )
+    .unwrap();
+    let names = |request: TypedRequest<Character>| -> Vec<String> {
+        request
+            .apply(&characters)
+            .into_iter()
+            .map(|character| character.name)
+            .collect()
+    };
+
+    let request = RequestBuilder::<Character>::typed()
+        .filter(Gender::filter_eq([Gender::Male]))
+        .build()
+        .unwrap();
+    assert_eq!(names(request), vec!["Adrahil I", "Adrahil II", "Aegnor"]);
+
+    let request = RequestBuilder::<Character>::typed()
+        .filter(Hair::filter_eq([Hair::Grey]))
+        .build()
+        .unwrap();
+    assert_eq!(
+        names(request),
+        vec!["Adanel", "Adrahil I", "Aegnor", "Agarwaen"]
+    );
+
+    // The filters match the characters that `parse` normalizes to the same values.
+    let request = RequestBuilder::<Character>::typed()
+        .filter(Gender::filter_ne([Gender::Male, Gender::Female]))
+        .build()
+        .unwrap();
+    let genders: Vec<_> = request
+        .apply(&characters)
+        .iter()
+        .map(Character::parsed_gender)
+        .collect();
+    assert_eq!(genders, vec![None]);
+}
@@ -0,0 +1,642 @@
+use futures::TryStreamExt;
+use lotr_api::{
+    attribute::{BookAttribute, CharacterAttribute},
+    filter::{Filter, Operator},
+    request::{decode::DecodeMode, GetUrl},
+    sort::{Sort, SortOrder},
+    transport::FixtureTransport,
+    ApiObject, Book, Chapter, Character, Client, ClientBuilder, Error, Item, ItemType, Movie,
+    MovieId, Pagination, Quote, RequestBuilder, RetryPolicy,
+};
+use reqwest::StatusCode;
+
+const BOOKS: &str = r#"{
+  "docs": [
+    { "_id": "5cf5805fb53e011a64671582", "name": "The Fellowship Of The Ring" },
+    { "_id": "5cf58077b53e011a64671583", "name": "The Two Towers" },
+    { "_id": "5cf58080b53e011a64671584", "name": "The Return Of The King" }
+  ],
+  "total": 3,
+  "limit": 1000,
+  "offset": 0,
+  "page": 1,
+  "pages": 1
+}"#;
+
+const BOOKS_PAGE_1: &str = r#"{
+  "docs": [
+    { "_id": "5cf5805fb53e011a64671582", "name": "The Fellowship Of The Ring" },
+    { "_id": "5cf58077b53e011a64671583", "name": "The Two Towers" }
+  ],
+  "total": 3,
+  "limit": 2,
+  "offset": 0,
+  "page": 1,
+  "pages": 2
+}"#;
+
+const BOOKS_PAGE_2: &str = r#"{
+  "docs": [
+    { "_id": "5cf58080b53e011a64671584", "name": "The Return Of The King" }
+  ],
+  "total": 3,
+  "limit": 2,
+  "offset": 0,
+  "page": 2,
+  "pages": 2
+}"#;
+
+fn client(transport: &FixtureTransport) -> Client {
+    ClientBuilder::new("token".to_string())
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_books() {
+    let transport = FixtureTransport::new().with_response("book", BOOKS);
+    let books = client(&transport).get_books().await.unwrap();
+
+    assert_eq!(books.len(), 3);
+    assert_eq!(books[1].name, "The Two Towers");
+    assert_eq!(transport.requests(), vec!["book"]);
+}
+
+#[tokio::test]
+async fn test_get_request() {
+    let transport = FixtureTransport::new().with_response("book?limit=2&page=1", BOOKS_PAGE_1);
+    let request = RequestBuilder::new(ItemType::Book)
+        .pagination(Pagination::new(2, 0, 1))
+        .build()
+        .unwrap();
+
+    let books = client(&transport).get(request).await.unwrap();
+    assert_eq!(books.len(), 2);
+    assert!(matches!(&books[0], Item::Book(book) if book.name == "The Fellowship Of The Ring"));
+}
+
+#[tokio::test]
+async fn test_stream_pages() {
+    let transport = FixtureTransport::new()
+        .with_response("book?limit=2&page=1", BOOKS_PAGE_1)
+        .with_response("book?limit=2&page=2", BOOKS_PAGE_2);
+    let request = RequestBuilder::new(ItemType::Book)
+        .pagination(Pagination::new(2, 0, 1))
+        .build()
+        .unwrap();
+
+    let books: Vec<Item> = client(&transport)
+        .stream(request)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(books.len(), 3);
+    assert_eq!(
+        transport.requests(),
+        vec!["book?limit=2&page=1", "book?limit=2&page=2"]
+    );
+}
+
+#[tokio::test]
+async fn test_missing_fixture() {
+    let transport = FixtureTransport::new();
+    assert!(matches!(
+        client(&transport).get_movies().await,
+        Err(Error::UnexpectedStatus {
+            status: StatusCode::NOT_FOUND,
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_server_error() {
+    let transport =
+        FixtureTransport::new().with_status("book", StatusCode::INTERNAL_SERVER_ERROR, "Oops");
+    match client(&transport).get_books().await {
+        Err(Error::Server { status, body }) => {
+            assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(body, "Oops");
+        }
+        result => panic!("Expected a server error, got {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn test_get_page() {
+    let transport = FixtureTransport::new()
+        .with_response("book?limit=2&page=1", BOOKS_PAGE_1)
+        .with_response("book?limit=2&page=2", BOOKS_PAGE_2);
+    let client = client(&transport);
+    let request = RequestBuilder::new(ItemType::Book)
+        .pagination(Pagination::new(2, 0, 1))
+        .build()
+        .unwrap();
+
+    let page = client.get_page(request).await.unwrap();
+    assert_eq!(page.items().len(), 2);
+    assert_eq!(page.total(), 3);
+    assert_eq!(page.limit(), 2);
+    assert_eq!(page.page(), Some(1));
+    assert_eq!(page.pages(), Some(2));
+    assert!(page.has_next());
+
+    let page = client
+        .get_page_typed::<Book>(page.next_request().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(page.items()[0].name, "The Return Of The King");
+    assert!(!page.has_next());
+}
+
+#[tokio::test]
+async fn test_get_typed() {
+    let transport = FixtureTransport::new().with_response("book?sort=name:desc", BOOKS);
+    let request = RequestBuilder::<Book>::typed()
+        .sort(Sort::new(SortOrder::Descending, BookAttribute::Name))
+        .build()
+        .unwrap();
+
+    let books: Vec<Book> = client(&transport).get_typed(request).await.unwrap();
+    assert_eq!(books.len(), 3);
+}
+
+const CHARACTERS_BY_REALM_PAGE_1: &str = r#"{
+  "docs": [
+    { "_id": "5cd99d4bde30eff6ebccfc01", "name": "Pippin", "realm": null },
+    { "_id": "5cd99d4bde30eff6ebccfc02", "name": "Faramir", "realm": "Gondor" },
+    { "_id": "5cd99d4bde30eff6ebccfc03", "name": "Boromir", "realm": "Gondor" }
+  ],
+  "total": 5,
+  "limit": 3,
+  "offset": 0,
+  "page": 1,
+  "pages": 2
+}"#;
+
+const CHARACTERS_BY_REALM_PAGE_2: &str = r#"{
+  "docs": [
+    { "_id": "5cd99d4bde30eff6ebccfc04", "name": "Denethor II", "realm": "Gondor" },
+    { "_id": "5cd99d4bde30eff6ebccfc05", "name": "Théoden", "realm": "Rohan" }
+  ],
+  "total": 5,
+  "limit": 3,
+  "offset": 0,
+  "page": 2,
+  "pages": 2
+}"#;
+
+#[tokio::test]
+async fn test_multi_key_sort() {
+    let transport = FixtureTransport::new()
+        .with_response("character?sort=realm:asc", CHARACTERS_BY_REALM_PAGE_1)
+        .with_response(
+            "character?sort=realm:asc&limit=3&page=2",
+            CHARACTERS_BY_REALM_PAGE_2,
+        );
+    let client = client(&transport);
+    let request = RequestBuilder::<Character>::typed()
+        .sort(Sort::new(SortOrder::Ascending, CharacterAttribute::Realm))
+        .sort(Sort::new(SortOrder::Ascending, CharacterAttribute::Name))
+        .build()
+        .unwrap();
+
+    let characters: Vec<Character> = client.get_typed(request.clone()).await.unwrap();
+    let names: Vec<_> = characters.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["Pippin", "Boromir", "Denethor II", "Faramir", "Théoden"]
+    );
+    assert_eq!(
+        transport.requests(),
+        vec![
+            "character?sort=realm:asc",
+            "character?sort=realm:asc&limit=3&page=2"
+        ]
+    );
+
+    // The pagination of the request is applied to the sorted items.
+    let request = RequestBuilder::<Character>::typed()
+        .sort(Sort::new(SortOrder::Ascending, CharacterAttribute::Realm))
+        .sort(Sort::new(SortOrder::Descending, CharacterAttribute::Name))
+        .pagination(Pagination::new(2, 0, 2))
+        .build()
+        .unwrap();
+    let page = client
+        .get_page_typed::<Character>(request.into_request())
+        .await
+        .unwrap();
+    let names: Vec<_> = page.items().iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Denethor II", "Boromir"]);
+    assert_eq!(page.total(), 5);
+    assert_eq!(page.page(), Some(2));
+    assert_eq!(page.pages(), Some(3));
+    assert_eq!(
+        page.next_request().unwrap().get_url(),
+        "character?sort=realm:asc&sort=name:desc&limit=2&page=3"
+    );
+}
+
+#[tokio::test]
+async fn test_local_filter_on_null() {
+    let transport = FixtureTransport::new()
+        .with_response("character?sort=realm:asc", CHARACTERS_BY_REALM_PAGE_1)
+        .with_response(
+            "character?sort=realm:asc&limit=3&page=2",
+            CHARACTERS_BY_REALM_PAGE_2,
+        );
+    // The API can't express the `or`, so the client evaluates it on the JSON of the
+    // API, where Pippin's realm is `null`: like in `apply`, it doesn't exist.
+    let request = RequestBuilder::<Character>::typed()
+        .sort(Sort::new(SortOrder::Ascending, CharacterAttribute::Realm))
+        .filter(Filter::Or(vec![
+            Filter::Exists(CharacterAttribute::Realm, false),
+            Filter::Match(
+                CharacterAttribute::Name,
+                Operator::Eq,
+                vec!["Théoden".into()],
+            ),
+        ]))
+        .build()
+        .unwrap();
+
+    let client = client(&transport);
+    let characters: Vec<Character> = client.get_typed(request.clone()).await.unwrap();
+    let names: Vec<_> = characters.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Pippin", "Théoden"]);
+
+    let all: Vec<Character> = client
+        .stream_typed(
+            RequestBuilder::new(ItemType::Character)
+                .sort(Sort::new(
+                    SortOrder::Ascending,
+                    CharacterAttribute::Realm.into(),
+                ))
+                .build()
+                .unwrap(),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(request.apply(&all), characters);
+}
+
+#[tokio::test]
+async fn test_stream_local_plan() {
+    let transport = FixtureTransport::new()
+        .with_response("character?sort=realm:asc", CHARACTERS_BY_REALM_PAGE_1)
+        .with_response(
+            "character?sort=realm:asc&limit=3&page=2",
+            CHARACTERS_BY_REALM_PAGE_2,
+        );
+    let request = RequestBuilder::new(ItemType::Character)
+        .sort(Sort::new(
+            SortOrder::Ascending,
+            CharacterAttribute::Realm.into(),
+        ))
+        .sort(Sort::new(
+            SortOrder::Descending,
+            CharacterAttribute::Name.into(),
+        ))
+        .pagination(Pagination::new(2, 0, 1))
+        .build()
+        .unwrap();
+
+    // The items are fetched once, and the three pages of the stream are made from them.
+    let characters: Vec<Character> = client(&transport)
+        .stream_typed(request)
+        .try_collect()
+        .await
+        .unwrap();
+    let names: Vec<_> = characters.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["Pippin", "Faramir", "Denethor II", "Boromir", "Théoden"]
+    );
+    assert_eq!(
+        transport.requests(),
+        vec![
+            "character?sort=realm:asc",
+            "character?sort=realm:asc&limit=3&page=2"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_local_filters() {
+    let transport = FixtureTransport::new()
+        .with_response("character?sort=realm:asc", CHARACTERS_BY_REALM_PAGE_1)
+        .with_response(
+            "character?sort=realm:asc&limit=3&page=2",
+            CHARACTERS_BY_REALM_PAGE_2,
+        );
+    let request = RequestBuilder::<Character>::typed()
+        .filter(Filter::Or(vec![
+            Filter::Match(
+                CharacterAttribute::Realm,
+                Operator::Eq,
+                vec!["Rohan".into()],
+            ),
+            Filter::Regex(
+                CharacterAttribute::Name,
+                Operator::Eq,
+                "^(bo|pi)".to_string(),
+                "i".to_string(),
+            ),
+        ]))
+        .sort(Sort::new(SortOrder::Ascending, CharacterAttribute::Realm))
+        .pagination(Pagination::new(2, 0, 1))
+        .build()
+        .unwrap();
+    assert_eq!(request.request().plan().local_filters().len(), 1);
+
+    let page = client(&transport)
+        .get_page_typed::<Character>(request.into_request())
+        .await
+        .unwrap();
+    let names: Vec<_> = page.items().iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Pippin", "Boromir"]);
+    assert_eq!(page.total(), 3);
+    assert_eq!(page.pages(), Some(2));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+const MOVIES_BY_ID: &str = r#"{
+  "docs": [
+    {
+      "_id": "5cd95395de30eff6ebccde5c",
+      "name": "The Fellowship of the Ring",
+      "runtimeInMinutes": 178,
+      "budgetInMillions": 93,
+      "boxOfficeRevenueInMillions": 871.5,
+      "academyAwardNominations": 13,
+      "academyAwardWins": 4,
+      "rottenTomatoesScore": 91
+    },
+    {
+      "_id": "5cd95395de30eff6ebccde5d",
+      "name": "The Return of the King",
+      "runtimeInMinutes": 201,
+      "budgetInMillions": 94,
+      "boxOfficeRevenueInMillions": 1120,
+      "academyAwardNominations": 11,
+      "academyAwardWins": 11,
+      "rottenTomatoesScore": 95
+    }
+  ],
+  "total": 2,
+  "limit": 1000,
+  "offset": 0,
+  "page": 1,
+  "pages": 1
+}"#;
+
+const CHARACTERS_BY_ID: &str = r#"{
+  "docs": [
+    { "_id": "5cd99d4bde30eff6ebccfc15", "name": "Frodo Baggins", "realm": "" },
+    { "_id": "5cd99d4bde30eff6ebccfea0", "name": "Gandalf", "realm": "" }
+  ],
+  "total": 2,
+  "limit": 1000,
+  "offset": 0,
+  "page": 1,
+  "pages": 1
+}"#;
+
+const BOOKS_BY_ID: &str = r#"{
+  "docs": [
+    { "_id": "5cf5805fb53e011a64671582", "name": "The Fellowship Of The Ring" },
+    { "_id": "5cf58077b53e011a64671583", "name": "The Two Towers" }
+  ],
+  "total": 2,
+  "limit": 1000,
+  "offset": 0,
+  "page": 1,
+  "pages": 1
+}"#;
+
+fn quote(id: &str, dialog: &str, movie: &str, character: &str) -> Quote {
+    Quote {
+        _id: id.parse().unwrap(),
+        dialog: Some(dialog.to_string()),
+        movie: movie.parse().unwrap(),
+        character: character.parse().unwrap(),
+        id: id.parse().unwrap(),
+        extra: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_hydrate_quotes() {
+    let transport = FixtureTransport::new()
+        .with_response(
+            "movie?_id=5cd95395de30eff6ebccde5c,5cd95395de30eff6ebccde5d",
+            MOVIES_BY_ID,
+        )
+        .with_response(
+            "character?_id=5cd99d4bde30eff6ebccfc15,5cd99d4bde30eff6ebccfea0",
+            CHARACTERS_BY_ID,
+        );
+    let quotes = vec![
+        quote(
+            "5cd96e05de30eff6ebcce7e9",
+            "You shall not pass!",
+            "5cd95395de30eff6ebccde5c",
+            "5cd99d4bde30eff6ebccfea0",
+        ),
+        quote(
+            "5cd96e05de30eff6ebcce7ea",
+            "I will take the Ring to Mordor.",
+            "5cd95395de30eff6ebccde5c",
+            "5cd99d4bde30eff6ebccfc15",
+        ),
+        quote(
+            "5cd96e05de30eff6ebcce7eb",
+            "I am glad to be with you, Samwise Gamgee.",
+            "5cd95395de30eff6ebccde5d",
+            "5cd99d4bde30eff6ebccfc15",
+        ),
+    ];
+
+    let hydrated = client(&transport).hydrate(quotes.clone()).await.unwrap();
+    let names: Vec<_> = hydrated
+        .iter()
+        .map(|quote| (quote.movie.name.as_str(), quote.character.name.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("The Fellowship of the Ring", "Gandalf"),
+            ("The Fellowship of the Ring", "Frodo Baggins"),
+            ("The Return of the King", "Frodo Baggins"),
+        ]
+    );
+    assert_eq!(hydrated[0].quote, quotes[0]);
+    // Each movie and character is only fetched once, in a single request per item type.
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_hydrate_chapters() {
+    let transport = FixtureTransport::new().with_response(
+        "book?_id=5cf5805fb53e011a64671582,5cf58077b53e011a64671583",
+        BOOKS_BY_ID,
+    );
+    let chapter = |id: &str, name: &str, book: &str| Chapter {
+        _id: id.parse().unwrap(),
+        chapter_name: name.to_string(),
+        book: book.parse().unwrap(),
+        extra: Default::default(),
+    };
+    let chapters = vec![
+        chapter(
+            "6091b6d6d58360f988133b8b",
+            "A Long-expected Party",
+            "5cf5805fb53e011a64671582",
+        ),
+        chapter(
+            "6091b6d6d58360f988133b9d",
+            "The Departure of Boromir",
+            "5cf58077b53e011a64671583",
+        ),
+    ];
+
+    let hydrated = client(&transport).hydrate(chapters).await.unwrap();
+    let books: Vec<_> = hydrated
+        .iter()
+        .map(|chapter| chapter.book.name.as_str())
+        .collect();
+    assert_eq!(books, vec!["The Fellowship Of The Ring", "The Two Towers"]);
+
+    // The book of the chapter doesn't exist.
+    let transport = FixtureTransport::new().with_response(
+        "book?_id=5cf58080b53e011a64671584",
+        r#"{"docs":[],"total":0,"limit":1000,"offset":0,"page":1,"pages":1}"#,
+    );
+    let result = client(&transport)
+        .hydrate(vec![chapter(
+            "6091b6d6d58360f988133bb3",
+            "The Grey Havens",
+            "5cf58080b53e011a64671584",
+        )])
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::NotFound { item_type: ItemType::Book, ref id }) if id == "5cf58080b53e011a64671584"
+    ));
+}
+
+#[tokio::test]
+async fn test_hydrate_many_references() {
+    // More books than the ids that fit in a single request.
+    let book_id = |index: usize| format!("5cf5805fb53e011a6467{:04x}", index);
+    let books_by_id = |indexes: std::ops::Range<usize>| {
+        let docs: Vec<_> = indexes
+            .clone()
+            .map(|index| format!(r#"{{"_id":"{}","name":"Book {}"}}"#, book_id(index), index))
+            .collect();
+        format!(
+            r#"{{"docs":[{}],"total":{},"limit":1000,"offset":0,"page":1,"pages":1}}"#,
+            docs.join(","),
+            indexes.len()
+        )
+    };
+    let ids = |indexes: std::ops::Range<usize>| indexes.map(book_id).collect::<Vec<_>>().join(",");
+    let transport = FixtureTransport::new()
+        .with_response(format!("book?_id={}", ids(0..100)), books_by_id(0..100))
+        .with_response(format!("book?_id={}", ids(100..150)), books_by_id(100..150));
+
+    let chapters: Vec<_> = (0..150)
+        .map(|index| Chapter {
+            _id: format!("6091b6d6d58360f98813{:04x}", index)
+                .parse()
+                .unwrap(),
+            chapter_name: format!("Chapter {}", index),
+            book: book_id(index).parse().unwrap(),
+            extra: Default::default(),
+        })
+        .collect();
+
+    let hydrated = client(&transport).hydrate(chapters).await.unwrap();
+    assert_eq!(hydrated.len(), 150);
+    for (index, chapter) in hydrated.iter().enumerate() {
+        assert_eq!(chapter.book.name, format!("Book {}", index));
+    }
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_lenient_decode() {
+    // The dialog of the second quote has the wrong type.
+    let quotes = r#"{
+      "docs": [
+        {
+          "_id": "5cd96e05de30eff6ebcce7e9",
+          "dialog": "Deagol!",
+          "movie": "5cd95395de30eff6ebccde5d",
+          "character": "5cd99d4bde30eff6ebcd0e0c",
+          "id": "5cd96e05de30eff6ebcce7e9"
+        },
+        {
+          "_id": "5cd96e05de30eff6ebcce7ea",
+          "dialog": 42,
+          "movie": "5cd95395de30eff6ebccde5d",
+          "character": "5cd99d4bde30eff6ebcd0e0c",
+          "id": "5cd96e05de30eff6ebcce7ea"
+        }
+      ],
+      "total": 2,
+      "limit": 1000,
+      "offset": 0,
+      "page": 1,
+      "pages": 1
+    }"#;
+    let transport = FixtureTransport::new().with_response("quote", quotes);
+    let request = RequestBuilder::new(ItemType::Quote).build().unwrap();
+
+    let result = client(&transport).get_page(request.clone()).await;
+    assert!(matches!(result, Err(Error::Decode { .. })));
+
+    let client = ClientBuilder::new("token".to_string())
+        .transport(transport)
+        .decode_mode(DecodeMode::Lenient)
+        .build()
+        .unwrap();
+    let page = client.get_page(request.clone()).await.unwrap();
+    assert_eq!(page.items().len(), 1);
+    assert_eq!(page.total(), 2);
+    assert_eq!(page.skipped().len(), 1);
+    assert_eq!(page.skipped()[0].index(), 1);
+    assert_eq!(page.skipped()[0].document()["dialog"], 42);
+
+    // The other methods can't report the skipped documents, so they stay strict.
+    let result = client.get(request.clone()).await;
+    assert!(matches!(result, Err(Error::Decode { .. })));
+    let result: Result<Vec<_>, _> = client.stream(request).try_collect().await;
+    assert!(matches!(result, Err(Error::Decode { .. })));
+    assert!(matches!(
+        client.get_quotes().await,
+        Err(Error::Decode { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_get_by_id() {
+    let transport = FixtureTransport::new().with_response(
+        "movie/5cd95395de30eff6ebccde5b",
+        include_str!("fixtures/movie_id.json"),
+    );
+    let client = client(&transport);
+    let id: MovieId = "5cd95395de30eff6ebccde5b".parse().unwrap();
+
+    let movie: Movie = client.get_by_id(&id).await.unwrap();
+    assert_eq!(movie.id(), &id);
+    assert_eq!(client.get_movie_by_id(&id).await.unwrap(), movie);
+
+    let item = Item::from(movie);
+    assert_eq!(item.item_type(), ItemType::Movie);
+    assert_eq!(item.id(), "5cd95395de30eff6ebccde5b");
+    assert_eq!(item.display_name(), Some("The Two Towers"));
+}
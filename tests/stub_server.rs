@@ -0,0 +1,232 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use lotr_api::{Client, ClientBuilder, Error, RetryPolicy};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+const BOOKS: &str = r#"{"docs":[{"_id":"5cf5805fb53e011a64671582","name":"The Fellowship Of The Ring"}],"total":1,"limit":1000,"offset":0,"page":1,"pages":1}"#;
+
+/// Starts a server that answers each request with the next of the given
+/// responses ( status, extra headers, body ), repeating the last one.
+/// Returns its url and the number of requests it received.
+async fn stub_server(
+    responses: Vec<(u16, &'static str, &'static str)>,
+) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v2/", listener.local_addr().unwrap());
+    let counter = Arc::new(AtomicUsize::new(0));
+    let requests = counter.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let index = requests.fetch_add(1, Ordering::SeqCst);
+            let (status, headers, body) = responses[index.min(responses.len() - 1)];
+
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let response = format!(
+                "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                status,
+                body.len(),
+                headers,
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.unwrap();
+        }
+    });
+
+    (url, counter)
+}
+
+fn client(url: &str, retry_policy: RetryPolicy) -> Client {
+    ClientBuilder::new("token".to_string())
+        .base_url(url)
+        .retry_policy(retry_policy.base_delay(Duration::from_millis(1)))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_retry_server_errors() {
+    let (url, requests) = stub_server(vec![(503, "", ""), (500, "", ""), (200, "", BOOKS)]).await;
+    let client = client(&url, RetryPolicy::new(3));
+
+    let books = client.get_books().await.unwrap();
+    assert_eq!(books.len(), 1);
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_retry_after() {
+    let (url, requests) =
+        stub_server(vec![(429, "Retry-After: 0\r\n", ""), (200, "", BOOKS)]).await;
+    let client = client(&url, RetryPolicy::new(3));
+
+    assert!(client.get_books().await.is_ok());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_retry_after_too_long() {
+    let (url, requests) =
+        stub_server(vec![(429, "Retry-After: 3600\r\n", ""), (200, "", BOOKS)]).await;
+    let client = client(&url, RetryPolicy::new(3));
+
+    match client.get_books().await {
+        Err(Error::RateLimited {
+            retry_after: Some(retry_after),
+        }) => assert_eq!(retry_after, Duration::from_secs(3600)),
+        result => panic!("Expected a rate limit error, got {:?}", result),
+    }
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_retry_server_error_with_rate_limit_reset() {
+    // The reset header comes with every response: on a server error it is
+    // not the time to wait before retrying.
+    let (url, requests) = stub_server(vec![
+        (
+            503,
+            "X-RateLimit-Remaining: 0\r\nX-RateLimit-Reset: 4102444800\r\n",
+            "",
+        ),
+        (200, "", BOOKS),
+    ])
+    .await;
+    let client = client(&url, RetryPolicy::new(3));
+
+    assert!(client.get_books().await.is_ok());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_give_up_after_max_attempts() {
+    let (url, requests) = stub_server(vec![(500, "", "")]).await;
+    let client = client(&url, RetryPolicy::new(4));
+
+    assert!(matches!(
+        client.get_books().await,
+        Err(Error::Server { .. })
+    ));
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn test_no_retry_on_client_error() {
+    let (url, requests) = stub_server(vec![(404, "", ""), (200, "", BOOKS)]).await;
+    let client = client(&url, RetryPolicy::new(3));
+
+    assert!(client.get_books().await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_no_retry_policy() {
+    let (url, requests) = stub_server(vec![(503, "", ""), (200, "", BOOKS)]).await;
+    let client = client(&url, RetryPolicy::none());
+
+    assert!(client.get_books().await.is_err());
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_unauthorized() {
+    let (url, _) = stub_server(vec![(
+        401,
+        "",
+        r#"{"success":false,"message":"Unauthorized."}"#,
+    )])
+    .await;
+    let client = client(&url, RetryPolicy::new(3));
+
+    assert!(matches!(client.get_books().await, Err(Error::Unauthorized)));
+}
+
+#[tokio::test]
+async fn test_decode_error() {
+    let (url, _) = stub_server(vec![(200, "", r#"{"docs":"not a list"}"#)]).await;
+    let client = client(&url, RetryPolicy::none());
+
+    match client.get_books().await {
+        Err(Error::Decode {
+            url, body_snippet, ..
+        }) => {
+            assert!(url.ends_with("/v2/book"));
+            assert_eq!(body_snippet, r#"{"docs":"not a list"}"#);
+        }
+        result => panic!("Expected a decode error, got {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn test_not_found() {
+    let (url, _) = stub_server(vec![(
+        200,
+        "",
+        r#"{"docs":[],"total":0,"limit":1000,"offset":0,"page":1,"pages":1}"#,
+    )])
+    .await;
+    let client = client(&url, RetryPolicy::none());
+
+    match client
+        .get_book_by_id(&"5cf5805fb53e011a64671582".parse().unwrap())
+        .await
+    {
+        Err(Error::NotFound { item_type, id }) => {
+            assert_eq!(item_type, lotr_api::ItemType::Book);
+            assert_eq!(id, "5cf5805fb53e011a64671582");
+        }
+        result => panic!("Expected a not found error, got {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn test_not_found_status() {
+    let (url, _) = stub_server(vec![(
+        404,
+        "",
+        r#"{"success":false,"message":"Not found."}"#,
+    )])
+    .await;
+    let client = client(&url, RetryPolicy::none());
+
+    match client
+        .get_movie_by_id(&"5cd95395de30eff6ebccde5d".parse().unwrap())
+        .await
+    {
+        Err(Error::NotFound { item_type, id }) => {
+            assert_eq!(item_type, lotr_api::ItemType::Movie);
+            assert_eq!(id, "5cd95395de30eff6ebccde5d");
+        }
+        result => panic!("Expected a not found error, got {:?}", result),
+    }
+
+    assert!(matches!(
+        client
+            .get_quotes_from_character(&"5cd99d4bde30eff6ebccfbbe".parse().unwrap())
+            .await,
+        Err(Error::NotFound {
+            item_type: lotr_api::ItemType::Character,
+            ..
+        })
+    ));
+}